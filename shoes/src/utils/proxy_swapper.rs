@@ -25,11 +25,26 @@ impl ProxySwapper {
     /// This creates a single-hop chain containing the provided config
     /// and updates the selector.
     pub fn swap_to_node(&self, node: &ClientConfig) {
-        // 1. Build a single-hop chain for the node
-        let chain_hop = ClientChainHop::Single(ConfigSelection::Config(node.clone()));
-        let chain = ClientChain {
-            hops: OneOrSome::One(chain_hop),
+        self.swap_to_chain(std::slice::from_ref(node));
+    }
+
+    /// Swap the current proxy to a multi-hop chain.
+    ///
+    /// `hops` are ordered from first (closest to client) to last (closest to target),
+    /// e.g. `[upstream, node]` reaches the node through the upstream proxy.
+    /// An empty slice resets to a direct connection.
+    pub fn swap_to_chain(&self, hops: &[ClientConfig]) {
+        // 1. Build one single-config hop per entry
+        let mut hops: Vec<ClientChainHop> = hops
+            .iter()
+            .map(|hop| ClientChainHop::Single(ConfigSelection::Config(hop.clone())))
+            .collect();
+        let hops = match hops.len() {
+            0 => return self.reset_to_direct(),
+            1 => OneOrSome::One(hops.remove(0)),
+            _ => OneOrSome::Some(hops),
         };
+        let chain = ClientChain { hops };
 
         // 2. Build the chain group
         let chain_group = build_client_chain_group(
@@ -0,0 +1,312 @@
+//! 代理控制通道 (Proxy Control Channel)
+//!
+//! 抓取进程中的代理 Actor 在本地回环端口上接受 `spider proxy` 子命令的查询与指令。
+//! 监听端口与访问令牌写入 `cache/proxy_control.json` (按进程号区分)，抓取结束时移除。
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use flume::Sender;
+use rand::Rng;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::actors::proxy::ProxyMsg;
+
+/// 等待代理 Actor 响应的上限 (含订阅下载与节点探测)
+const REPLY_TIMEOUT: Duration = Duration::from_secs(120);
+/// 连接控制端口的超时
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+/// 单条请求的长度上限
+const MAX_REQUEST_BYTES: u64 = 64 * 1024;
+
+/// 控制指令
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlRequest {
+    Status,
+    List,
+    Test { query: Option<String> },
+    Use { query: Option<String> },
+    Refresh,
+    Pause,
+    Resume,
+}
+
+/// 控制端点记录 (`proxy_control.json`)
+#[derive(Debug, Serialize, Deserialize)]
+struct Endpoint {
+    pid: u32,
+    port: u16,
+    token: String,
+}
+
+impl Endpoint {
+    fn path(cache_path: &str) -> PathBuf {
+        PathBuf::from(cache_path).join("proxy_control.json")
+    }
+
+    fn load(cache_path: &str) -> Option<Self> {
+        std::fs::read(Self::path(cache_path))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+    }
+}
+
+/// 单次请求报文
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    token: String,
+    request: ControlRequest,
+}
+
+/// 将控制指令转交代理 Actor 并等待回复
+pub async fn dispatch(tx: &Sender<ProxyMsg>, request: ControlRequest) -> Result<Value> {
+    match request {
+        ControlRequest::Status => ask(tx, |reply| ProxyMsg::Status { reply }).await,
+        ControlRequest::List => ask(tx, |reply| ProxyMsg::List { reply }).await,
+        ControlRequest::Test { query } => {
+            ask_fallible(tx, |reply| ProxyMsg::Test { query, reply }).await
+        }
+        ControlRequest::Use { query } => {
+            ask_fallible(tx, |reply| ProxyMsg::Use { query, reply }).await
+        }
+        ControlRequest::Refresh => ask_fallible(tx, |reply| ProxyMsg::Refresh { reply }).await,
+        ControlRequest::Pause => ask(tx, |reply| ProxyMsg::Pause { reply }).await,
+        ControlRequest::Resume => ask(tx, |reply| ProxyMsg::Resume { reply }).await,
+    }
+}
+
+async fn ask<T: Serialize>(
+    tx: &Sender<ProxyMsg>,
+    msg: impl FnOnce(oneshot::Sender<T>) -> ProxyMsg,
+) -> Result<Value> {
+    let (reply, rx) = oneshot::channel();
+    tx.send(msg(reply))
+        .map_err(|_| anyhow!("Proxy manager is not running"))?;
+
+    let value = tokio::time::timeout(REPLY_TIMEOUT, rx)
+        .await
+        .context("Proxy manager timed out")?
+        .context("Proxy manager stopped before replying")?;
+    Ok(serde_json::to_value(value)?)
+}
+
+async fn ask_fallible<T: Serialize>(
+    tx: &Sender<ProxyMsg>,
+    msg: impl FnOnce(oneshot::Sender<Result<T>>) -> ProxyMsg,
+) -> Result<Value> {
+    let (reply, rx) = oneshot::channel();
+    tx.send(msg(reply))
+        .map_err(|_| anyhow!("Proxy manager is not running"))?;
+
+    let value = tokio::time::timeout(REPLY_TIMEOUT, rx)
+        .await
+        .context("Proxy manager timed out")?
+        .context("Proxy manager stopped before replying")??;
+    Ok(serde_json::to_value(value)?)
+}
+
+/// 控制通道服务端
+///
+/// 随抓取进程存活；析构时停止监听并移除本进程写入的端点记录。
+pub struct ControlServer {
+    path: PathBuf,
+    task: JoinHandle<()>,
+}
+
+impl ControlServer {
+    /// 监听回环地址的随机端口并登记端点
+    pub async fn start(cache_path: &str, tx: Sender<ProxyMsg>) -> Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+        let endpoint = Endpoint {
+            pid: std::process::id(),
+            port: listener.local_addr()?.port(),
+            token: format!("{:032x}", rand::rng().random::<u128>()),
+        };
+
+        let path = Endpoint::path(cache_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_vec(&endpoint)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        info!("Proxy control listening on 127.0.0.1:{}", endpoint.port);
+
+        let token = endpoint.token;
+        let task = tokio::spawn(async move {
+            loop {
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        warn!("Proxy control accept failed: {}", e);
+                        continue;
+                    }
+                };
+                let tx = tx.clone();
+                let token = token.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = serve(stream, &token, &tx).await {
+                        debug!("Proxy control connection failed: {:#}", e);
+                    }
+                });
+            }
+        });
+
+        Ok(Self { path, task })
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        self.task.abort();
+        // 仅移除本进程登记的端点，其他抓取进程可能已覆盖该记录
+        let pid = std::fs::read(&self.path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Endpoint>(&bytes).ok())
+            .map(|endpoint| endpoint.pid);
+        if pid == Some(std::process::id()) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// 处理单个连接：读取一行请求，回复一行 `Result`
+async fn serve(stream: TcpStream, token: &str, tx: &Sender<ProxyMsg>) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    BufReader::new(reader.take(MAX_REQUEST_BYTES))
+        .read_line(&mut line)
+        .await?;
+    // 客户端探测端点是否存活时不发送请求
+    if line.trim().is_empty() {
+        return Ok(());
+    }
+
+    let envelope: Envelope = serde_json::from_str(&line)?;
+    let result = if envelope.token != token {
+        Err("Invalid proxy control token".to_string())
+    } else {
+        dispatch(tx, envelope.request)
+            .await
+            .map_err(|e| format!("{:#}", e))
+    };
+
+    let mut reply = serde_json::to_vec(&result)?;
+    reply.push(b'\n');
+    writer.write_all(&reply).await?;
+    writer.shutdown().await?;
+    Ok(())
+}
+
+/// 控制通道客户端
+pub struct ControlClient {
+    addr: SocketAddr,
+    token: String,
+}
+
+impl ControlClient {
+    /// 查找正在运行的抓取进程 (无端点记录或无法连接时返回 `None`)
+    pub async fn connect(cache_path: &str) -> Option<Self> {
+        let endpoint = Endpoint::load(cache_path)?;
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, endpoint.port));
+        match tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(addr)).await {
+            Ok(Ok(_)) => Some(Self {
+                addr,
+                token: endpoint.token,
+            }),
+            _ => {
+                debug!("Stale proxy control endpoint (pid {})", endpoint.pid);
+                None
+            }
+        }
+    }
+
+    /// 发送指令并解析回复
+    pub async fn call<T: DeserializeOwned>(&self, request: ControlRequest) -> Result<T> {
+        let mut stream = TcpStream::connect(self.addr)
+            .await
+            .context("Running scrape is no longer reachable")?;
+
+        let envelope = Envelope {
+            token: self.token.clone(),
+            request,
+        };
+        let mut line = serde_json::to_vec(&envelope)?;
+        line.push(b'\n');
+        stream.write_all(&line).await?;
+
+        let mut reply = String::new();
+        tokio::time::timeout(REPLY_TIMEOUT, BufReader::new(stream).read_line(&mut reply))
+            .await
+            .context("Running scrape timed out")??;
+        if reply.is_empty() {
+            bail!("Running scrape closed the control connection");
+        }
+
+        match serde_json::from_str::<std::result::Result<Value, String>>(&reply)? {
+            Ok(value) => Ok(serde_json::from_value(value)?),
+            Err(e) => Err(anyhow!(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::proxy::{NodeInfo, ProxyStatus};
+
+    #[tokio::test]
+    async fn forwards_requests_to_the_running_actor() {
+        let dir = std::env::temp_dir().join(format!("spider-control-{}", std::process::id()));
+        let cache = dir.to_string_lossy().into_owned();
+
+        let (tx, rx) = flume::unbounded();
+        tokio::spawn(async move {
+            while let Ok(msg) = rx.recv_async().await {
+                match msg {
+                    ProxyMsg::Status { reply } => {
+                        let _ = reply.send(ProxyStatus {
+                            enabled: true,
+                            total: 3,
+                            ..Default::default()
+                        });
+                    }
+                    ProxyMsg::Use { query, reply } => {
+                        let _ = reply.send(Err(anyhow!("No proxy node matches '{:?}'", query)));
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        let server = ControlServer::start(&cache, tx).await.unwrap();
+        let client = ControlClient::connect(&cache)
+            .await
+            .expect("running endpoint");
+
+        let status: ProxyStatus = client.call(ControlRequest::Status).await.unwrap();
+        assert_eq!(status.total, 3);
+
+        let err = client
+            .call::<Option<NodeInfo>>(ControlRequest::Use {
+                query: Some("KR".into()),
+            })
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("No proxy node matches"), "{}", err);
+
+        // 抓取结束后端点随之移除，查询改走离线模式
+        drop(server);
+        assert!(ControlClient::connect(&cache).await.is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,246 @@
+//! 节点健康检查 (Node Health Check)
+//!
+//! 经 shoes 链路 (配置前置跳板时为 跳板 -> 节点 两跳) 直接向探测地址发起请求，记录各节点的延迟与可用性，
+//! 供轮换时跳过不可用节点并优先选择低延迟节点。结果持久化于 `cache_path`。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context as _, Result, bail};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tracing::{debug, warn};
+use url::Url;
+
+use shoes::address::{Address, NetLocation};
+use shoes::config::{ClientChain, ClientChainHop, ClientConfig, ConfigSelection};
+use shoes::network::tcp::chain_builder::build_client_chain_group;
+use shoes::option_util::{NoneOrSome, OneOrSome};
+use shoes::resolver::Resolver;
+
+use crate::core::config::HealthCheckConfig;
+use crate::utils::unix_now;
+
+/// 健康表文件名
+const HEALTH_FILE: &str = "proxy_health.json";
+/// 读取响应状态行的上限
+const STATUS_LINE_LIMIT: usize = 1024;
+
+/// 单个节点的探测结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodeHealth {
+    /// 最近一次探测是否成功
+    pub ok: bool,
+    /// 最近一次成功探测的延迟 (毫秒)
+    pub latency_ms: Option<u64>,
+    /// 最近一次探测时间 (Unix 秒)
+    pub last_checked: u64,
+    /// 连续失败次数
+    pub failures: u32,
+    pub last_error: Option<String>,
+}
+
+/// 节点健康表 (按节点指纹索引)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HealthTable {
+    nodes: HashMap<String, NodeHealth>,
+}
+
+impl HealthTable {
+    pub fn path(cache_path: &str) -> PathBuf {
+        Path::new(cache_path).join(HEALTH_FILE)
+    }
+
+    /// 读取健康表，文件缺失或损坏时返回空表
+    pub fn load(cache_path: &str) -> Self {
+        std::fs::read(Self::path(cache_path))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, cache_path: &str) {
+        let path = Self::path(cache_path);
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_vec_pretty(self) {
+            Ok(bytes) => {
+                if let Err(e) = std::fs::write(&path, bytes) {
+                    warn!("Failed to save proxy health table: {}", e);
+                }
+            }
+            Err(e) => warn!("Failed to serialize proxy health table: {}", e),
+        }
+    }
+
+    pub fn get(&self, fingerprint: &str) -> Option<&NodeHealth> {
+        self.nodes.get(fingerprint)
+    }
+
+    /// 记录一次探测结果
+    pub fn record(&mut self, fingerprint: &str, result: &Result<Duration>) {
+        let entry = self.nodes.entry(fingerprint.to_string()).or_default();
+        entry.last_checked = unix_now();
+        match result {
+            Ok(latency) => {
+                entry.ok = true;
+                entry.latency_ms = Some(latency.as_millis() as u64);
+                entry.failures = 0;
+                entry.last_error = None;
+            }
+            Err(e) => {
+                entry.ok = false;
+                entry.failures = entry.failures.saturating_add(1);
+                entry.last_error = Some(format!("{:#}", e));
+            }
+        }
+    }
+
+    /// 丢弃已不在节点列表中的记录
+    pub fn retain(&mut self, fingerprints: &[&str]) {
+        self.nodes
+            .retain(|fp, _| fingerprints.contains(&fp.as_str()));
+    }
+
+    /// 按健康状况对节点下标排序
+    ///
+    /// 可用节点按延迟升序在前，未探测节点保持原顺序居中，不可用节点排除在外。
+    pub fn rank<'a>(&self, fingerprints: impl Iterator<Item = &'a str>) -> Vec<usize> {
+        let mut healthy = Vec::new();
+        let mut unknown = Vec::new();
+        for (i, fp) in fingerprints.enumerate() {
+            match self.nodes.get(fp) {
+                Some(h) if h.ok => healthy.push((h.latency_ms.unwrap_or(u64::MAX), i)),
+                Some(_) => {}
+                None => unknown.push(i),
+            }
+        }
+        healthy.sort_unstable();
+        healthy.into_iter().map(|(_, i)| i).chain(unknown).collect()
+    }
+}
+
+/// 经节点链路请求探测地址，返回收到响应状态行的耗时
+///
+/// `hops` 自客户端一侧起排列，末跳为被探测的节点。
+pub async fn probe_node(
+    hops: &[ClientConfig],
+    resolver: Arc<dyn Resolver>,
+    url: &Url,
+    timeout: Duration,
+) -> Result<Duration> {
+    let host = url.host_str().context("Probe URL has no host")?;
+    let port = url.port_or_known_default().unwrap_or(80);
+    let target = NetLocation::new(Address::from(host)?, port);
+
+    let chain = ClientChain {
+        hops: OneOrSome::Some(
+            hops.iter()
+                .map(|hop| ClientChainHop::Single(ConfigSelection::Config(hop.clone())))
+                .collect(),
+        ),
+    };
+    let group = build_client_chain_group(NoneOrSome::Some(vec![chain]), resolver.clone());
+
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: spider-health\r\nConnection: close\r\n\r\n",
+        &url[url::Position::BeforePath..url::Position::AfterQuery],
+        host
+    );
+
+    let started = Instant::now();
+    let exchange = async {
+        let setup = group.connect_tcp(target, &resolver).await?;
+        let mut stream = setup.client_stream;
+        stream.write_all(request.as_bytes()).await?;
+        stream.flush().await?;
+
+        let mut buf = setup.early_data.unwrap_or_default();
+        let mut chunk = [0u8; 512];
+        while !buf.contains(&b'\n') && buf.len() < STATUS_LINE_LIMIT {
+            let n = stream.read(&mut chunk).await?;
+            if n == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        Ok::<_, std::io::Error>(buf)
+    };
+
+    let buf = tokio::time::timeout(timeout, exchange)
+        .await
+        .context("Probe timed out")??;
+    let elapsed = started.elapsed();
+
+    let line = String::from_utf8_lossy(&buf);
+    let line = line.lines().next().unwrap_or_default();
+    let status: u16 = line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .with_context(|| format!("Malformed response: {:?}", line))?;
+    if !(200..400).contains(&status) {
+        bail!("Probe returned HTTP {}", status);
+    }
+    Ok(elapsed)
+}
+
+/// 并发探测一组节点并写入健康表
+pub async fn probe_all(
+    nodes: Vec<(String, Vec<ClientConfig>)>,
+    resolver: Arc<dyn Resolver>,
+    config: &HealthCheckConfig,
+    table: &parking_lot::Mutex<HealthTable>,
+) -> Result<()> {
+    let url = Url::parse(&config.url).context("Invalid proxy.health.url")?;
+    if url.scheme() != "http" {
+        bail!("proxy.health.url must use http://, got {}", url.scheme());
+    }
+    let timeout = Duration::from_secs(config.timeout_secs);
+
+    let mut results = futures::stream::iter(nodes)
+        .map(|(fp, hops)| {
+            let resolver = resolver.clone();
+            let url = &url;
+            async move {
+                let result = probe_node(&hops, resolver, url, timeout).await;
+                (fp, result)
+            }
+        })
+        .buffer_unordered(config.concurrency.max(1));
+
+    while let Some((fp, result)) = results.next().await {
+        match &result {
+            Ok(latency) => debug!("Node {} healthy ({} ms)", short(&fp), latency.as_millis()),
+            Err(e) => debug!("Node {} unhealthy: {:#}", short(&fp), e),
+        }
+        table.lock().record(&fp, &result);
+    }
+    Ok(())
+}
+
+/// 指纹缩写 (日志用)
+pub fn short(fingerprint: &str) -> &str {
+    &fingerprint[..fingerprint.len().min(8)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_by_latency_and_skips_failures() {
+        let mut table = HealthTable::default();
+        table.record("slow", &Ok(Duration::from_millis(300)));
+        table.record("fast", &Ok(Duration::from_millis(50)));
+        table.record("dead", &Err(anyhow::anyhow!("timeout")));
+
+        let ranked = table.rank(["slow", "dead", "new", "fast"].into_iter());
+        assert_eq!(ranked, vec![3, 0, 2]);
+        assert_eq!(table.get("dead").unwrap().failures, 1);
+    }
+}
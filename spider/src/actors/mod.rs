@@ -1 +1,4 @@
+pub mod control;
+pub mod health;
 pub mod proxy;
+pub mod quarantine;
@@ -4,11 +4,14 @@
 //! 使用 shoes 库在进程内直接运行代理核心，无需外部二进制依赖。
 
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
-use anyhow::{Result, Context as _};
+use anyhow::{Result, Context as _, anyhow};
 use flume::{Receiver, Sender};
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
@@ -25,34 +28,204 @@ use shoes::resolver::{NativeResolver, Resolver};
 use shoes::network::tcp::tcp_server::start_servers;
 use shoes::ProxySwapper;
 
+use crate::actors::health::{self, HealthTable, NodeHealth};
+use crate::actors::quarantine::QuarantineTable;
 use crate::core::config::AppConfig;
-use crate::utils::subscription::{ProxyNode, fetch_subscription_urls};
+use crate::core::error::BlockReason;
+use crate::network::egress;
+use crate::utils::node_filter::{NodeFilter, RegionPreference};
+use crate::utils::region;
+use crate::utils::subscription::{ProxyNode, load_nodes, parse_upstream};
+use crate::utils::unix_now;
 
 /// 代理控制指令
 #[derive(Debug)]
 pub enum ProxyMsg {
-    /// 切换到下一个可用节点
+    /// 切换指定出口通道到下一个可用节点
+    ///
+    /// 携带站点与阻断原因时，当前节点先对该站点进入冷却，随后跳过该站点冷却中的节点。
     Rotate {
+        lane: usize,
+        site: Option<String>,
+        reason: Option<BlockReason>,
         reply: Option<oneshot::Sender<()>>,
     },
-    /// 暂停/恢复代理服务 (预留)
-    Pause,
-    Resume,
+    /// 查询指定出口通道的节点指纹 (直连模式返回 `None`)
+    Current {
+        lane: usize,
+        reply: oneshot::Sender<Option<String>>,
+    },
+    /// 站点开始使用代理：当前节点不符合该站点地区偏好或正处于冷却时切换
+    Bind {
+        site: String,
+        reply: oneshot::Sender<()>,
+    },
+    /// 立即重新拉取订阅并合并节点列表
+    Refresh {
+        reply: oneshot::Sender<Result<RefreshSummary>>,
+    },
+    /// 列出全部节点概况
+    List {
+        reply: oneshot::Sender<Vec<NodeInfo>>,
+    },
+    /// 立即探测与查询匹配的节点 (`None` 表示全部节点)，结果写入健康表
+    Test {
+        query: Option<String>,
+        reply: oneshot::Sender<Result<Vec<NodeInfo>>>,
+    },
+    /// 固定使用与查询唯一匹配的节点 (`None` 解除固定)；固定期间忽略轮换
+    Use {
+        query: Option<String>,
+        reply: oneshot::Sender<Result<Option<NodeInfo>>>,
+    },
+    /// 查询代理运行状态
+    Status { reply: oneshot::Sender<ProxyStatus> },
+    /// 暂停自动轮换：各通道保持当前节点，期间忽略轮换并停止后台探测
+    Pause { reply: oneshot::Sender<()> },
+    /// 恢复自动轮换与后台探测
+    Resume { reply: oneshot::Sender<()> },
+}
+
+/// 节点概况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeInfo {
+    pub index: usize,
+    pub fingerprint: String,
+    pub tag: String,
+    pub protocol: String,
+    pub region: Option<String>,
+    pub address: String,
+    pub health: Option<NodeHealth>,
+    /// 处于冷却期的站点及剩余秒数
+    pub quarantined: Vec<(String, u64)>,
+    /// 是否为主通道当前出口节点
+    pub current: bool,
+    /// 正在使用该节点的出口通道
+    pub lanes: Vec<usize>,
+}
+
+/// 代理运行状态
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProxyStatus {
+    /// `proxy.enabled`
+    pub enabled: bool,
+    pub paused: bool,
+    /// 当前节点是否由 `spider proxy use` 固定
+    pub pinned: bool,
+    pub total: usize,
+    /// 健康检查未判定为不可用的节点数
+    pub usable: usize,
+    /// 主通道当前出口节点 (直连时为 `None`)
+    pub current: Option<NodeInfo>,
+    /// 各出口通道的节点 (直连时为空)
+    pub lanes: Vec<NodeInfo>,
+    /// 主通道的前置跳板 (协议与地址)
+    pub upstream: Option<String>,
+}
+
+/// 订阅刷新结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RefreshSummary {
+    /// 合并后的节点总数
+    pub total: usize,
+    pub added: usize,
+    pub removed: usize,
+    /// 当前节点已不在订阅中但仍被保留
+    pub kept_current: bool,
+}
+
+/// 已加载的代理节点
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub fingerprint: String,
+    pub tag: String,
+    /// 由标签推断的地区代码
+    pub region: Option<&'static str>,
+    pub config: ClientConfig,
+}
+
+/// 后台订阅拉取完成通知
+struct RefreshDone {
+    result: Result<Vec<Node>>,
+    reply: Option<oneshot::Sender<Result<RefreshSummary>>>,
 }
 
+/// 订阅缓存到期检查周期上限
+const REFRESH_CHECK_INTERVAL: Duration = Duration::from_secs(600);
+
+/// 持久化的代理状态 (`proxy_state.json`)
 #[derive(Debug, Serialize, Deserialize)]
-struct ProxyState {
-    last_node_fingerprint: String,
+pub struct ProxyState {
+    /// 主通道最近使用的节点指纹
+    pub last_node_fingerprint: String,
+    /// 上次的节点由用户固定，启动时沿用而非跳过
+    #[serde(default)]
+    pub pinned: bool,
+    /// 下次启动沿用上次的节点而非跳过 (如刚为其导入凭据)，启动后即清除
+    #[serde(default)]
+    pub resume: bool,
+}
+
+impl ProxyState {
+    fn path(cache_path: &str) -> PathBuf {
+        PathBuf::from(cache_path).join("proxy_state.json")
+    }
+
+    pub fn load(cache_path: &str) -> Option<Self> {
+        std::fs::read(Self::path(cache_path))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+    }
+
+    pub fn save(&self, cache_path: &str) {
+        let path = Self::path(cache_path);
+        // 确保存储目录存在
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_vec(self) {
+            Ok(bytes) => {
+                if let Err(e) = std::fs::write(&path, bytes) {
+                    warn!("Failed to save proxy state: {}", e);
+                }
+            }
+            Err(e) => warn!("Failed to serialize proxy state: {}", e),
+        }
+    }
+}
+
+/// 出口通道：独立的本地监听端口及其绑定的节点
+struct Lane {
+    selector: Arc<ReloadableProxySelector>,
+    swapper: ProxySwapper,
+    /// 绑定的节点下标
+    node: usize,
+    /// 最近绑定的站点 (决定前置跳板)
+    site: Option<String>,
 }
 
 pub struct ProxyManager {
     config: Arc<AppConfig>,
     rx: Receiver<ProxyMsg>,
-    selector: Arc<ReloadableProxySelector>,
     resolver: Arc<dyn Resolver>,
-    swapper: ProxySwapper,
-    nodes: Vec<(String, ClientConfig)>,
-    current_node_index: usize,
+    nodes: Vec<Node>,
+    /// 出口通道 (下标 0 为主通道，监听 `proxy_port`)
+    lanes: Vec<Lane>,
+    /// 节点健康表 (后台探测任务与轮换共享)
+    health: Arc<Mutex<HealthTable>>,
+    /// 是否有探测任务正在进行
+    probing: Arc<AtomicBool>,
+    /// 节点按站点隔离表
+    quarantine: QuarantineTable,
+    /// 后台订阅拉取结果回传信道
+    refresh_tx: Sender<RefreshDone>,
+    refresh_rx: Receiver<RefreshDone>,
+    /// 当前节点已固定，不随轮换切换
+    pinned: bool,
+    /// 已暂停，流量直连
+    paused: bool,
+    /// 离线模式：仅供查询与固定节点，不监听端口、不轮换、不写回启动状态
+    detached: bool,
 }
 
 impl ProxyManager {
@@ -62,40 +235,79 @@ impl ProxyManager {
         let config_clone = config.clone();
 
         let handle = tokio::spawn(async move {
-            let manager = Self::new(config_clone, rx);
+            let manager = Self::new(config_clone, rx, false);
             manager.run().await;
         });
 
         (tx, handle)
     }
 
-    fn new(config: Arc<AppConfig>, rx: Receiver<ProxyMsg>) -> Self {
+    /// 启动离线代理管理器，供未在抓取时的 `spider proxy` 子命令查询节点
+    ///
+    /// 沿用上次的节点而不轮换，除固定节点等显式操作外不写回 `proxy_state.json`。
+    pub fn start_detached(config: Arc<AppConfig>) -> (Sender<ProxyMsg>, JoinHandle<()>) {
+        let (tx, rx) = flume::unbounded();
+        let handle = tokio::spawn(Self::new(config, rx, true).run());
+        (tx, handle)
+    }
+
+    fn new(config: Arc<AppConfig>, rx: Receiver<ProxyMsg>, detached: bool) -> Self {
         let resolver = Arc::new(NativeResolver::new());
         // 初始为空选择器（使用直连，直到节点加载完成）
         use shoes::config::{ClientChain, ClientChainHop, ClientConfig, ConfigSelection};
         use shoes::utils::option::{NoneOrSome, OneOrSome};
         use shoes::network::tcp::chain_builder::build_client_chain_group;
 
-        // 创建直连 chain group
-        let chain_hop = ClientChainHop::Single(ConfigSelection::Config(ClientConfig::default()));
-        let chain = ClientChain {
-            hops: OneOrSome::One(chain_hop),
-        };
-        let chain_group = build_client_chain_group(NoneOrSome::Some(vec![chain]), resolver.clone());
+        let lane_count = if detached { 1 } else { egress::count(&config) };
+        let lanes = (0..lane_count)
+            .map(|_| {
+                // 创建直连 chain group
+                let chain_hop =
+                    ClientChainHop::Single(ConfigSelection::Config(ClientConfig::default()));
+                let chain = ClientChain {
+                    hops: OneOrSome::One(chain_hop),
+                };
+                let chain_group =
+                    build_client_chain_group(NoneOrSome::Some(vec![chain]), resolver.clone());
 
-        let selector = Arc::new(ReloadableProxySelector::new(
-            ClientProxySelector::new_with_chain_group(chain_group)
-        ));
-        let swapper = ProxySwapper::new(selector.clone(), resolver.clone());
+                let selector = Arc::new(ReloadableProxySelector::new(
+                    ClientProxySelector::new_with_chain_group(chain_group),
+                ));
+                let swapper = ProxySwapper::new(selector.clone(), resolver.clone());
+                Lane {
+                    selector,
+                    swapper,
+                    node: 0,
+                    site: None,
+                }
+            })
+            .collect();
+        let health = Arc::new(Mutex::new(HealthTable::load(&config.cache_path)));
+        let quarantine = QuarantineTable::load(&config.cache_path);
+        let (refresh_tx, refresh_rx) = flume::unbounded();
 
         Self {
             config,
             rx,
-            selector,
             resolver,
-            swapper,
             nodes: Vec::new(),
-            current_node_index: 0,
+            lanes,
+            health,
+            probing: Arc::new(AtomicBool::new(false)),
+            quarantine,
+            refresh_tx,
+            refresh_rx,
+            pinned: false,
+            paused: false,
+            detached,
+        }
+    }
+
+    /// 订阅缓存有效期
+    fn subscription_ttl(&self) -> Duration {
+        match self.config.proxy.subscription_ttl_secs {
+            0 => Duration::MAX,
+            secs => Duration::from_secs(secs),
         }
     }
 
@@ -104,23 +316,50 @@ impl ProxyManager {
             info!("Proxy disabled by configuration (proxy.enabled = false)");
             info!("Operating in DIRECT CONNECTION mode - all traffic will bypass proxy");
             // 启动直连模式的本地监听服务（仍然提供接口，但流量直连）
-            if let Err(e) = self.start_local_server().await {
-                error!("Failed to start local server in direct mode: {}", e);
-                return;
+            if !self.detached {
+                if let Err(e) = self.start_local_server().await {
+                    error!("Failed to start local server in direct mode: {}", e);
+                    return;
+                }
+                info!("Local proxy listening on 127.0.0.1:{} (DIRECT MODE)", self.config.proxy.proxy_port);
             }
-            info!("Local proxy listening on 127.0.0.1:{} (DIRECT MODE)", self.config.proxy.proxy_port);
 
             // 直连模式下仍然处理消息，但 rotate 不做任何事
             while let Ok(msg) = self.rx.recv_async().await {
                 match msg {
-                    ProxyMsg::Rotate { reply } => {
+                    ProxyMsg::Rotate { reply, .. } => {
                         debug!("Rotate requested but ignored in direct mode");
                         if let Some(tx) = reply {
                             let _ = tx.send(());
                         }
                     }
-                    ProxyMsg::Pause => {}
-                    ProxyMsg::Resume => {}
+                    ProxyMsg::Current { reply, .. } => {
+                        let _ = reply.send(None);
+                    }
+                    ProxyMsg::Bind { reply, .. } => {
+                        let _ = reply.send(());
+                    }
+                    ProxyMsg::Refresh { reply } => {
+                        let _ =
+                            reply.send(Err(anyhow!("Proxy is disabled (proxy.enabled = false)")));
+                    }
+                    ProxyMsg::List { reply } => {
+                        let _ = reply.send(Vec::new());
+                    }
+                    ProxyMsg::Test { reply, .. } => {
+                        let _ =
+                            reply.send(Err(anyhow!("Proxy is disabled (proxy.enabled = false)")));
+                    }
+                    ProxyMsg::Use { reply, .. } => {
+                        let _ =
+                            reply.send(Err(anyhow!("Proxy is disabled (proxy.enabled = false)")));
+                    }
+                    ProxyMsg::Status { reply } => {
+                        let _ = reply.send(ProxyStatus::default());
+                    }
+                    ProxyMsg::Pause { reply } | ProxyMsg::Resume { reply } => {
+                        let _ = reply.send(());
+                    }
                 }
             }
             return;
@@ -128,12 +367,18 @@ impl ProxyManager {
 
         info!("Initializing internal proxy core (shoes)...");
 
-        // 1. 获取订阅并解析节点
-        match self.fetch_and_parse_nodes().await {
+        // 筛选规则已在加载配置时校验；仍无效时不启动代理，绝不因配置错误退回直连
+        if let Err(e) = NodeFilter::new(&self.config.proxy.filter) {
+            error!("Invalid proxy filter, proxy not started: {:#}", e);
+            return;
+        }
+
+        // 1. 获取订阅并解析节点 (缓存未过期时直接复用)
+        match Self::fetch_and_parse_nodes(&self.config, Some(self.subscription_ttl())).await {
             Ok(nodes) => {
                 if nodes.is_empty() {
-                    warn!("No subscription URLs configured, operating in DIRECT CONNECTION mode");
-                    warn!("Add proxy.subscription_urls to config.toml to enable proxy rotation");
+                    warn!("No proxy nodes configured, operating in DIRECT CONNECTION mode");
+                    warn!("Add proxy.subscription_urls or proxy.static_nodes to enable proxy rotation");
                 } else {
                     info!("Loaded {} proxy nodes", nodes.len());
                 }
@@ -145,113 +390,690 @@ impl ProxyManager {
             }
         }
 
-        // 恢复上次的状态并跳过最后一个节点
-        if !self.nodes.is_empty() {
-            if let Some(state) = self.load_state() {
+        // 离线模式沿用上次的节点，仅供查询
+        if self.detached {
+            self.restore_detached();
+        } else if !self.nodes.is_empty() {
+            // 恢复上次的状态并跳过最后一个节点
+            if let Some(state) = ProxyState::load(&self.config.cache_path) {
                 // 查找上次使用的节点指纹对应的索引
-                let last_index = self.nodes.iter().position(|(fp, _)| *fp == state.last_node_fingerprint);
+                let last_index = self.nodes.iter().position(|n| n.fingerprint == state.last_node_fingerprint);
                 
-                if let Some(i) = last_index {
-                    // 如果找到了上次的节点，从下一个开始（跳过）
-                    self.current_node_index = (i + 1) % self.nodes.len();
+                if let (Some(i), true) = (last_index, state.pinned) {
+                    // 上次的节点由用户固定，继续使用
+                    self.lanes[0].node = i;
+                    self.pinned = true;
+                    info!("Using pinned proxy node #{}", i);
+                } else if let (Some(i), true) = (last_index, state.resume) {
+                    // 已为上次的节点导入凭据，本次沿用
+                    self.lanes[0].node = i;
+                    info!("Resuming proxy node #{} with imported credentials", i);
+                } else if let Some(i) = last_index {
+                    // 如果找到了上次的节点，跳过它并优先选择延迟最低的可用节点
+                    self.lanes[0].node = self
+                        .ranked_nodes(None)
+                        .into_iter()
+                        .find(|&j| j != i)
+                        .unwrap_or((i + 1) % self.nodes.len());
                     info!(
                         "Resuming from proxy node #{} (Last used fingerprint: {}, skipped)",
-                        self.current_node_index, 
+                        self.lanes[0].node,
                         &state.last_node_fingerprint.chars().take(8).collect::<String>()
                     );
                 } else {
                     // 如果找不到（节点列表变动），重置为0
-                    self.current_node_index = 0;
+                    self.lanes[0].node = 0;
                     info!(
                         "Last used node not found (Fingerprint: {}), resetting to node #0", 
                         &state.last_node_fingerprint.chars().take(8).collect::<String>()
                     );
                 }
             } else {
-                self.current_node_index = 0;
+                self.lanes[0].node = self.ranked_nodes(None).first().copied().unwrap_or(0);
             }
             // 立即保存新的起始状态，防止启动即崩溃导致状态未更新
             self.save_state();
+            self.assign_lanes();
         }
 
-        // 2. 启动本地监听服务
-        if let Err(e) = self.start_local_server().await {
-            error!("Failed to start local proxy server: {}", e);
-            return;
+        if !self.detached {
+            // 2. 启动本地监听服务
+            if let Err(e) = self.start_local_server().await {
+                error!("Failed to start local proxy server: {}", e);
+                return;
+            }
+
+            // 3. 应用初始节点
+            self.apply_all();
         }
 
-        // 3. 应用初始节点
-        self.apply_node();
+        // 4. 事件循环 (同时按周期触发节点健康检查与订阅刷新)
+        let config = self.config.clone();
+        let health = &config.proxy.health;
+        let mut ticker = tokio::time::interval(Duration::from_secs(health.interval_secs.max(1)));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        // 按较短周期检查缓存是否到期：未到期时仅读取缓存文件，也能及时采用其他进程的刷新结果
+        let refresh_enabled = !self.detached
+            && self.config.proxy.subscription_ttl_secs > 0
+            && self.config.proxy.has_node_sources();
+        let refresh_period = self.subscription_ttl().min(REFRESH_CHECK_INTERVAL);
+        let mut refresh_ticker =
+            tokio::time::interval_at(tokio::time::Instant::now() + refresh_period, refresh_period);
+        refresh_ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
-        // 4. 事件循环
-        while let Ok(msg) = self.rx.recv_async().await {
-            match msg {
-                ProxyMsg::Rotate { reply } => {
-                    self.rotate_node();
-                    if let Some(tx) = reply {
-                        let _ = tx.send(());
+        loop {
+            let probe_enabled =
+                health.enabled && !self.nodes.is_empty() && !self.paused && !self.detached;
+
+            tokio::select! {
+                msg = self.rx.recv_async() => {
+                    let Ok(msg) = msg else { break };
+                    match msg {
+                        ProxyMsg::Rotate { lane, site, reason, reply } => {
+                            let lane = lane.min(self.lanes.len() - 1);
+                            if self.paused {
+                                debug!("Rotate requested but ignored while paused");
+                            } else {
+                                if let (Some(site), Some(reason)) = (&site, &reason) {
+                                    self.quarantine_current(lane, site, reason);
+                                }
+                                if self.pinned {
+                                    warn!("Proxy node is pinned, ignoring rotation");
+                                } else {
+                                    self.rotate_node(lane, site.as_deref());
+                                }
+                            }
+                            if let Some(tx) = reply {
+                                let _ = tx.send(());
+                            }
+                        }
+                        ProxyMsg::Current { lane, reply } => {
+                            let _ = reply.send(self.current_fingerprint(lane));
+                        }
+                        ProxyMsg::Bind { site, reply } => {
+                            self.bind_site(&site);
+                            let _ = reply.send(());
+                        }
+                        ProxyMsg::Refresh { reply } => {
+                            if !self.config.proxy.has_node_sources() {
+                                let _ = reply.send(Err(anyhow!("No proxy node sources configured")));
+                            } else {
+                                self.spawn_refresh(None, Some(reply));
+                            }
+                        }
+                        ProxyMsg::List { reply } => {
+                            let nodes = (0..self.nodes.len()).map(|i| self.node_info(i)).collect();
+                            let _ = reply.send(nodes);
+                        }
+                        ProxyMsg::Test { query, reply } => self.spawn_test(query.as_deref(), reply),
+                        ProxyMsg::Use { query, reply } => {
+                            let _ = reply.send(self.use_node(query.as_deref()));
+                        }
+                        ProxyMsg::Status { reply } => {
+                            let _ = reply.send(self.status());
+                        }
+                        ProxyMsg::Pause { reply } => {
+                            if !self.paused {
+                                self.paused = true;
+                                info!("Proxy rotation paused, current nodes kept until resumed");
+                            }
+                            let _ = reply.send(());
+                        }
+                        ProxyMsg::Resume { reply } => {
+                            if self.paused {
+                                self.paused = false;
+                                info!("Proxy rotation resumed");
+                            }
+                            let _ = reply.send(());
+                        }
                     }
                 }
-                ProxyMsg::Pause => {
-                    info!("Proxy service paused (not implemented)");
-                }
-                ProxyMsg::Resume => {
-                    info!("Proxy service resumed (not implemented)");
+                _ = ticker.tick(), if probe_enabled => self.spawn_health_check(),
+                _ = refresh_ticker.tick(), if refresh_enabled => {
+                    self.spawn_refresh(Some(self.subscription_ttl()), None);
                 }
+                Ok(done) = self.refresh_rx.recv_async() => self.finish_refresh(done),
             }
         }
     }
 
-    fn get_state_path(&self) -> PathBuf {
-        PathBuf::from(&self.config.cache_path).join("proxy_state.json")
+    /// 离线模式：定位上次使用的节点 (无记录时取排序首位)，不跳过、不写回
+    fn restore_detached(&mut self) {
+        let state = ProxyState::load(&self.config.cache_path);
+        let last = state.as_ref().and_then(|state| {
+            self.nodes
+                .iter()
+                .position(|n| n.fingerprint == state.last_node_fingerprint)
+        });
+        self.pinned = last.is_some() && state.is_some_and(|state| state.pinned);
+        self.lanes[0].node = last
+            .or_else(|| self.ranked_nodes(None).first().copied())
+            .unwrap_or(0);
     }
 
-    fn load_state(&self) -> Option<ProxyState> {
-        let path = self.get_state_path();
-        if path.exists() {
-            if let Ok(file) = std::fs::File::open(&path) {
-                if let Ok(state) = serde_json::from_reader(file) {
-                    return Some(state);
-                }
+    fn bind_site(&mut self, site: &str) {
+        // 站点前置跳板不同时重建链路 (固定节点时同样生效)
+        for lane in 0..self.lanes.len() {
+            let previous = self.lanes[lane].site.replace(site.to_string());
+            if self.upstream_uri(previous.as_deref()) != self.upstream_uri(Some(site)) {
+                self.apply_node(lane);
             }
         }
-        None
+
+        if self.pinned || self.paused || self.nodes.is_empty() {
+            return;
+        }
+        for lane in 0..self.lanes.len() {
+            self.bind_lane(lane, site);
+        }
     }
 
-    fn save_state(&self) {
+    /// 通道节点正处于该站点冷却或不符合站点地区偏好时切换
+    fn bind_lane(&mut self, lane: usize, site: &str) {
+        let current = &self.nodes[self.lanes[lane].node];
+
+        if self
+            .quarantine
+            .is_quarantined(site, &current.fingerprint, unix_now())
+        {
+            info!("Current node is quarantined for site '{}', rotating", site);
+            self.rotate_node(lane, Some(site));
+            return;
+        }
+
+        let preference = self.region_preference(Some(site));
+        let busy = self.busy_nodes(lane);
+        let Some(best) = self
+            .ranked_nodes(Some(site))
+            .into_iter()
+            .find(|i| !busy.contains(i))
+        else {
+            return;
+        };
+        let rank = |region| preference.rank(region).unwrap_or(usize::MAX);
+        if rank(self.nodes[best].region) < rank(current.region) {
+            info!(
+                "Switching to a node in a preferred region for site '{}' ({:?})",
+                site, preference.regions
+            );
+            self.lanes[lane].node = best;
+            self.save_state();
+            self.apply_node(lane);
+        }
+    }
+
+    /// 其他通道正在使用的节点
+    fn busy_nodes(&self, lane: usize) -> Vec<usize> {
+        self.lanes
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != lane)
+            .map(|(_, l)| l.node)
+            .collect()
+    }
+
+    /// 为主通道以外的通道分配节点，尽量与其他通道互不相同 (固定节点时全部通道共用)
+    fn assign_lanes(&mut self) {
         if self.nodes.is_empty() {
             return;
         }
+        let primary = self.lanes[0].node;
+        if self.pinned {
+            self.lanes.iter_mut().for_each(|lane| lane.node = primary);
+            return;
+        }
 
-        let path = self.get_state_path();
-        // 确保存储目录存在
-        if let Some(parent) = path.parent() {
-            let _ = std::fs::create_dir_all(parent);
+        let ranked = self.ranked_nodes(None);
+        let mut used = vec![primary];
+        for lane in 1..self.lanes.len() {
+            let node = ranked
+                .iter()
+                .copied()
+                .chain(0..self.nodes.len())
+                .find(|i| !used.contains(i))
+                .unwrap_or_else(|| used[lane % self.nodes.len()]);
+            used.push(node);
+            self.lanes[lane].node = node;
         }
-        
-        if let Ok(file) = std::fs::File::create(&path) {
-            // 保存当前节点的指纹
-            let fingerprint = &self.nodes[self.current_node_index].0;
-            let state = ProxyState {
-                last_node_fingerprint: fingerprint.clone(),
+    }
+
+    /// 在后台拉取订阅，完成后经 `refresh_rx` 回到事件循环合并
+    ///
+    /// `max_age` 为 `None` 时忽略缓存强制下载。
+    fn spawn_refresh(
+        &self,
+        max_age: Option<Duration>,
+        reply: Option<oneshot::Sender<Result<RefreshSummary>>>,
+    ) {
+        let config = self.config.clone();
+        let tx = self.refresh_tx.clone();
+
+        tokio::spawn(async move {
+            let result = Self::fetch_and_parse_nodes(&config, max_age).await;
+            let _ = tx.send(RefreshDone { result, reply });
+        });
+    }
+
+    fn finish_refresh(&mut self, done: RefreshDone) {
+        let result = done
+            .result
+            .and_then(|nodes| self.merge_nodes(nodes))
+            .inspect_err(|e| {
+                warn!(
+                    "Subscription refresh failed, keeping current nodes: {:#}",
+                    e
+                )
+            });
+
+        if let Some(reply) = done.reply {
+            let _ = reply.send(result);
+        }
+    }
+
+    /// 以新的节点列表替换当前列表
+    ///
+    /// 当前节点不在新列表中时仍保留，避免刷新打断正在使用的出口。
+    fn merge_nodes(&mut self, mut nodes: Vec<Node>) -> Result<RefreshSummary> {
+        if nodes.is_empty() {
+            return Err(anyhow!("Subscription returned no usable nodes"));
+        }
+
+        let added = nodes
+            .iter()
+            .filter(|n| {
+                !self
+                    .nodes
+                    .iter()
+                    .any(|old| old.fingerprint == n.fingerprint)
+            })
+            .count();
+        let removed = self
+            .nodes
+            .iter()
+            .filter(|n| !nodes.iter().any(|new| new.fingerprint == n.fingerprint))
+            .count();
+
+        let mut summary = RefreshSummary {
+            total: nodes.len(),
+            added,
+            removed,
+            kept_current: false,
+        };
+        if added == 0 && removed == 0 {
+            debug!("Subscription unchanged ({} nodes)", nodes.len());
+            return Ok(summary);
+        }
+
+        let was_direct = self.nodes.is_empty();
+        let best = self
+            .ranked_nodes_of(&nodes, None)
+            .first()
+            .copied()
+            .unwrap_or(0);
+        for lane in 0..self.lanes.len() {
+            let current = self.nodes.get(self.lanes[lane].node).cloned();
+            self.lanes[lane].node = match current {
+                Some(node) => match nodes
+                    .iter()
+                    .position(|new| new.fingerprint == node.fingerprint)
+                {
+                    Some(i) => i,
+                    None => {
+                        nodes.push(node);
+                        summary.kept_current = true;
+                        summary.total += 1;
+                        nodes.len() - 1
+                    }
+                },
+                None => best,
             };
-            let _ = serde_json::to_writer(file, &state);
         }
+        self.nodes = nodes;
+
+        info!(
+            "Subscription refreshed: {} nodes (+{} / -{}){}",
+            summary.total,
+            summary.added,
+            summary.removed,
+            if summary.kept_current {
+                ", current node kept until next rotation"
+            } else {
+                ""
+            }
+        );
+
+        self.save_state();
+        if was_direct {
+            self.assign_lanes();
+            self.apply_all();
+        }
+        if added > 0 && self.config.proxy.health.enabled {
+            self.spawn_health_check();
+        }
+        Ok(summary)
     }
 
-    async fn fetch_and_parse_nodes(&self) -> Result<Vec<(String, ClientConfig)>> {
-        let urls = &self.config.proxy.subscription_urls;
-        if urls.is_empty() {
+    /// 在后台并发探测全部节点 (上一轮未结束时跳过)
+    fn spawn_health_check(&self) {
+        if self.probing.swap(true, Ordering::AcqRel) {
+            debug!("Previous health check still running, skipping this round");
+            return;
+        }
+
+        let nodes = self.probe_chains(0..self.nodes.len());
+        let resolver = self.resolver.clone();
+        let config = self.config.clone();
+        let table = self.health.clone();
+        let probing = self.probing.clone();
+
+        tokio::spawn(async move {
+            let fingerprints: Vec<String> = nodes.iter().map(|(fp, _)| fp.clone()).collect();
+            match health::probe_all(nodes, resolver, &config.proxy.health, &table).await {
+                Ok(()) => {
+                    let mut table = table.lock();
+                    let fps: Vec<&str> = fingerprints.iter().map(String::as_str).collect();
+                    table.retain(&fps);
+                    let healthy = table.rank(fps.iter().copied()).len();
+                    info!(
+                        "Proxy health check finished: {}/{} node(s) usable",
+                        healthy,
+                        fps.len()
+                    );
+                    table.save(&config.cache_path);
+                }
+                Err(e) => warn!("Proxy health check skipped: {:#}", e),
+            }
+            probing.store(false, Ordering::Release);
+        });
+    }
+
+    /// 探测链路 (全局前置跳板 + 节点)，按节点指纹标识
+    fn probe_chains(
+        &self,
+        indices: impl Iterator<Item = usize>,
+    ) -> Vec<(String, Vec<ClientConfig>)> {
+        let upstream = self.upstream_config(None);
+        indices
+            .map(|i| {
+                let node = &self.nodes[i];
+                let hops = upstream.iter().cloned().chain([node.config.clone()]);
+                (node.fingerprint.clone(), hops.collect())
+            })
+            .collect()
+    }
+
+    /// 站点使用的前置跳板 URI (`[sites.<id>] upstream` 覆盖 `proxy.upstream`)
+    fn upstream_uri(&self, site: Option<&str>) -> Option<&str> {
+        let site = site.and_then(|id| self.config.sites.get(id));
+        self.config.proxy.upstream_for(site)
+    }
+
+    /// 站点使用的前置跳板配置 (加载配置时已校验)
+    fn upstream_config(&self, site: Option<&str>) -> Option<ClientConfig> {
+        let uri = self.upstream_uri(site)?;
+        parse_upstream(uri)
+            .inspect_err(|e| error!("Invalid proxy upstream {}: {:#}", uri, e))
+            .ok()
+    }
+
+    /// 按地区偏好与健康状况排序的节点下标
+    ///
+    /// 偏好地区在前，同地区内低延迟可用节点在前，排除不可用节点。
+    fn ranked_nodes(&self, site: Option<&str>) -> Vec<usize> {
+        self.ranked_nodes_of(&self.nodes, site)
+    }
+
+    fn ranked_nodes_of(&self, nodes: &[Node], site: Option<&str>) -> Vec<usize> {
+        let ranked = if self.config.proxy.health.enabled {
+            self.health
+                .lock()
+                .rank(nodes.iter().map(|n| n.fingerprint.as_str()))
+        } else {
+            (0..nodes.len()).collect()
+        };
+        self.region_preference(site)
+            .apply(ranked, |i| nodes[i].region)
+    }
+
+    /// 站点地区偏好 (`[sites.<id>]` 覆盖 `[proxy.filter]`)
+    fn region_preference(&self, site: Option<&str>) -> RegionPreference {
+        let filter = &self.config.proxy.filter;
+        let site = site.and_then(|id| self.config.sites.get(id));
+        RegionPreference::new(
+            site.and_then(|s| s.regions.as_deref())
+                .unwrap_or(&filter.regions),
+            site.and_then(|s| s.region_only)
+                .unwrap_or(filter.region_only),
+        )
+    }
+
+    /// 出口通道当前节点指纹 (无可用节点时视为直连)
+    fn current_fingerprint(&self, lane: usize) -> Option<String> {
+        let lane = lane.min(self.lanes.len() - 1);
+        self.nodes
+            .get(self.lanes[lane].node)
+            .map(|n| n.fingerprint.clone())
+    }
+
+    fn node_info(&self, index: usize) -> NodeInfo {
+        let node = &self.nodes[index];
+        NodeInfo {
+            index,
+            fingerprint: node.fingerprint.clone(),
+            tag: node.tag.clone(),
+            protocol: node.config.protocol.protocol_name().to_string(),
+            region: node.region.map(str::to_string),
+            address: node.config.address.to_string(),
+            health: self.health.lock().get(&node.fingerprint).cloned(),
+            quarantined: self.quarantine.active(&node.fingerprint, unix_now()),
+            current: index == self.lanes[0].node,
+            lanes: (0..self.lanes.len())
+                .filter(|&lane| self.lanes[lane].node == index)
+                .collect(),
+        }
+    }
+
+    fn status(&self) -> ProxyStatus {
+        let usable = if self.config.proxy.health.enabled {
+            self.health
+                .lock()
+                .rank(self.nodes.iter().map(|n| n.fingerprint.as_str()))
+                .len()
+        } else {
+            self.nodes.len()
+        };
+        ProxyStatus {
+            enabled: true,
+            paused: self.paused,
+            pinned: self.pinned,
+            total: self.nodes.len(),
+            usable,
+            current: (!self.nodes.is_empty()).then(|| self.node_info(self.lanes[0].node)),
+            lanes: match self.nodes.is_empty() {
+                true => Vec::new(),
+                false => self.lanes.iter().map(|l| self.node_info(l.node)).collect(),
+            },
+            upstream: self
+                .upstream_config(self.lanes[0].site.as_deref())
+                .map(|c| format!("{} {}", c.protocol.protocol_name(), c.address)),
+        }
+    }
+
+    /// 按序号、完整标签或标签片段 (忽略大小写) 查找节点
+    fn find_nodes(&self, query: &str) -> Vec<usize> {
+        let query = query.trim();
+        if let Ok(i) = query.parse::<usize>()
+            && i < self.nodes.len()
+        {
+            return vec![i];
+        }
+
+        let exact: Vec<usize> = (0..self.nodes.len())
+            .filter(|&i| self.nodes[i].tag == query)
+            .collect();
+        if !exact.is_empty() {
+            return exact;
+        }
+
+        let needle = query.to_lowercase();
+        (0..self.nodes.len())
+            .filter(|&i| self.nodes[i].tag.to_lowercase().contains(&needle))
+            .collect()
+    }
+
+    /// 固定节点；`query` 为 `None` 时解除固定
+    fn use_node(&mut self, query: Option<&str>) -> Result<Option<NodeInfo>> {
+        let Some(query) = query else {
+            if self.pinned {
+                self.pinned = false;
+                self.save_state();
+                self.assign_lanes();
+                (1..self.lanes.len()).for_each(|lane| self.apply_node(lane));
+                info!("Proxy node unpinned, automatic rotation resumed");
+            }
+            return Ok(None);
+        };
+
+        let index = match self.find_nodes(query).as_slice() {
+            [] => return Err(anyhow!("No proxy node matches '{}'", query)),
+            [i] => *i,
+            many => {
+                return Err(anyhow!(
+                    "'{}' matches {} nodes, use a longer tag or the node index",
+                    query,
+                    many.len()
+                ));
+            }
+        };
+
+        self.pinned = true;
+        for lane in 0..self.lanes.len() {
+            if self.lanes[lane].node != index {
+                self.lanes[lane].node = index;
+                self.apply_node(lane);
+            }
+        }
+        self.save_state();
+        info!("Pinned proxy node #{}", index);
+        Ok(Some(self.node_info(index)))
+    }
+
+    /// 在后台探测匹配的节点，完成后回复探测后的节点概况
+    fn spawn_test(&self, query: Option<&str>, reply: oneshot::Sender<Result<Vec<NodeInfo>>>) {
+        let indices = match query {
+            Some(query) => self.find_nodes(query),
+            None => (0..self.nodes.len()).collect(),
+        };
+        if indices.is_empty() {
+            let _ = reply.send(Err(match query {
+                Some(query) => anyhow!("No proxy node matches '{}'", query),
+                None => anyhow!("No proxy nodes loaded"),
+            }));
+            return;
+        }
+
+        let mut infos: Vec<NodeInfo> = indices.iter().map(|&i| self.node_info(i)).collect();
+        let nodes = self.probe_chains(indices.iter().copied());
+        let resolver = self.resolver.clone();
+        let config = self.config.clone();
+        let table = self.health.clone();
+
+        tokio::spawn(async move {
+            let result = health::probe_all(nodes, resolver, &config.proxy.health, &table)
+                .await
+                .map(|()| {
+                    let table = table.lock();
+                    for info in &mut infos {
+                        info.health = table.get(&info.fingerprint).cloned();
+                    }
+                    table.save(&config.cache_path);
+                    infos
+                });
+            let _ = reply.send(result);
+        });
+    }
+
+    fn save_state(&self) {
+        if self.nodes.is_empty() {
+            return;
+        }
+
+        // 保存当前节点的指纹
+        let fingerprint = &self.nodes[self.lanes[0].node].fingerprint;
+        if self.detached {
+            self.save_detached_pin(fingerprint);
+            return;
+        }
+        let state = ProxyState {
+            last_node_fingerprint: fingerprint.clone(),
+            pinned: self.pinned,
+            resume: false,
+        };
+        state.save(&self.config.cache_path);
+    }
+
+    /// 离线模式仅改写固定设置：固定节点时记录该节点，其余字段 (如导入凭据后的恢复标记) 保持不变
+    fn save_detached_pin(&self, fingerprint: &str) {
+        let previous = ProxyState::load(&self.config.cache_path);
+        let state = match previous {
+            Some(state) if !self.pinned || state.last_node_fingerprint == fingerprint => {
+                if state.pinned == self.pinned {
+                    return;
+                }
+                ProxyState {
+                    pinned: self.pinned,
+                    ..state
+                }
+            }
+            _ if !self.pinned => return,
+            _ => ProxyState {
+                last_node_fingerprint: fingerprint.to_string(),
+                pinned: true,
+                resume: false,
+            },
+        };
+        state.save(&self.config.cache_path);
+    }
+
+    async fn fetch_and_parse_nodes(
+        config: &AppConfig,
+        max_age: Option<Duration>,
+    ) -> Result<Vec<Node>> {
+        let proxy = &config.proxy;
+        if !proxy.has_node_sources() {
             return Ok(vec![]);
         }
 
-        let cache_path = PathBuf::from(&self.config.cache_path);
-        let proxy_nodes = fetch_subscription_urls(urls, &cache_path).await?;
+        // 筛选规则在加载时应用，缓存保存完整的订阅内容；本地来源每次重新读取
+        let filter = NodeFilter::new(&proxy.filter)?;
+        let cache_path = PathBuf::from(&config.cache_path);
+        let proxy_nodes = load_nodes(
+            &proxy.subscription_urls,
+            &proxy.static_nodes,
+            proxy.node_dir.as_deref().map(Path::new),
+            &cache_path,
+            max_age,
+        )
+        .await?;
+        let fetched = proxy_nodes.len();
 
-        let client_configs: Vec<(String, ClientConfig)> = proxy_nodes
+        let nodes: Vec<Node> = proxy_nodes
             .into_iter()
+            .filter(|node| match filter.check(node) {
+                Ok(()) => true,
+                Err(reason) => {
+                    debug!("Filtered out node {}: {}", node.tag(), reason);
+                    false
+                }
+            })
             .filter_map(|node| match node.to_shoes_config() {
-                Ok(cfg) => Some((node.fingerprint(), cfg)),
+                Ok(cfg) => Some(Node {
+                    fingerprint: node.fingerprint(),
+                    tag: node.tag().to_string(),
+                    region: region::detect(node.tag()),
+                    config: cfg,
+                }),
                 Err(e) => {
                     debug!("Skipping invalid node {}: {}", node.tag(), e);
                     None
@@ -259,11 +1081,30 @@ impl ProxyManager {
             })
             .collect();
 
-        Ok(client_configs)
+        if nodes.len() < fetched {
+            info!(
+                "Using {} of {} subscription nodes after filtering",
+                nodes.len(),
+                fetched
+            );
+        }
+        Ok(nodes)
     }
 
+    /// 为每条出口通道启动本地监听
     async fn start_local_server(&self) -> Result<()> {
-        let port = self.config.proxy.proxy_port;
+        for (lane, Lane { selector, .. }) in self.lanes.iter().enumerate() {
+            self.start_lane_server(egress::port(&self.config, lane), selector.clone())
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn start_lane_server(
+        &self,
+        port: u16,
+        selector: Arc<ReloadableProxySelector>,
+    ) -> Result<()> {
         // 绑定到 127.0.0.1
         let bind_addr = Address::Ipv4(Ipv4Addr::new(127, 0, 0, 1));
         let location = NetLocation::new(bind_addr, port);
@@ -285,7 +1126,7 @@ impl ProxyManager {
         // 启动服务，后台运行
         let _handles = start_servers(
             shoes::config::Config::Server(server_config),
-            self.selector.clone(),
+            selector,
             self.resolver.clone(),
         ).await.map_err(|e| anyhow::anyhow!("Server start error: {}", e))?;
 
@@ -293,33 +1134,152 @@ impl ProxyManager {
         Ok(())
     }
 
-    fn rotate_node(&mut self) {
+    /// 将通道当前节点对指定站点隔离
+    fn quarantine_current(&mut self, lane: usize, site: &str, reason: &BlockReason) {
+        if !self.config.proxy.quarantine.enabled {
+            return;
+        }
+        let Some(fingerprint) = self
+            .nodes
+            .get(self.lanes[lane].node)
+            .map(|n| n.fingerprint.clone())
+        else {
+            return;
+        };
+
+        let cooldown = self.quarantine.block(
+            site,
+            &fingerprint,
+            &reason.to_string(),
+            &self.config.proxy.quarantine,
+            unix_now(),
+        );
+        info!(
+            "Quarantined node {} for site '{}' ({}), cool-down {}s",
+            health::short(&fingerprint),
+            site,
+            reason,
+            cooldown
+        );
+
+        let fingerprints: Vec<&str> = self.nodes.iter().map(|n| n.fingerprint.as_str()).collect();
+        self.quarantine.retain(&fingerprints);
+        self.quarantine.save(&self.config.cache_path);
+    }
+
+    fn rotate_node(&mut self, lane: usize, site: Option<&str>) {
         if self.nodes.is_empty() {
             warn!("No proxy nodes available to rotate");
             return;
         }
 
-        self.current_node_index = (self.current_node_index + 1) % self.nodes.len();
+        let now = unix_now();
+        let len = self.nodes.len();
+        let busy = self.busy_nodes(lane);
+        let allowed = |i: usize, avoid_busy: bool| {
+            !(avoid_busy && busy.contains(&i))
+                && site.is_none_or(|site| {
+                    !self
+                        .quarantine
+                        .is_quarantined(site, &self.nodes[i].fingerprint, now)
+                })
+        };
+
+        // 沿健康排序依次前进，避免在两个最快节点之间来回切换；
+        // 当前节点不在可用列表中时直接选择最优节点，全部不可用时退回顺序轮换。
+        // 优先避开其他通道正在使用的节点
+        let current = self.lanes[lane].node;
+        let ranked_all = self.ranked_nodes(site);
+        let pick = |avoid_busy: bool| {
+            let ranked: Vec<usize> = ranked_all
+                .iter()
+                .copied()
+                .filter(|&i| i == current || allowed(i, avoid_busy))
+                .collect();
+            let round_robin = (1..=len)
+                .map(|step| (current + step) % len)
+                .find(|&i| i != current && allowed(i, avoid_busy));
+
+            match ranked.iter().position(|&i| i == current) {
+                Some(pos) if ranked.len() > 1 => Some(ranked[(pos + 1) % ranked.len()]),
+                Some(_) => round_robin,
+                None => ranked.first().copied().or(round_robin),
+            }
+        };
+        let next = pick(true).or_else(|| pick(false));
+
+        self.lanes[lane].node = match next {
+            Some(i) => i,
+            None => {
+                // 该站点所有节点均在冷却中，选择最早解除的节点
+                warn!(
+                    "All proxy nodes are quarantined for site '{}', using the earliest to recover",
+                    site.unwrap_or_default()
+                );
+                (0..len)
+                    .filter(|&i| i != current || len == 1)
+                    .min_by_key(|&i| {
+                        site.and_then(|site| self.quarantine.get(site, &self.nodes[i].fingerprint))
+                            .map(|e| e.until)
+                            .unwrap_or(0)
+                    })
+                    .unwrap_or(current)
+            }
+        };
         self.save_state(); // 保存新状态
-        self.apply_node();
+        self.apply_node(lane);
+    }
+
+    fn apply_all(&mut self) {
+        for lane in 0..self.lanes.len() {
+            self.apply_node(lane);
+        }
     }
 
-    fn apply_node(&mut self) {
+    fn apply_node(&mut self, lane: usize) {
         if self.nodes.is_empty() {
             return;
         }
 
-        let (_, node) = &self.nodes[self.current_node_index];
+        let index = self.lanes[lane].node;
+        let Node {
+            fingerprint,
+            tag,
+            region,
+            config: node,
+        } = &self.nodes[index];
         let protocol_name = node.protocol.protocol_name();
-        
+        let latency = self
+            .health
+            .lock()
+            .get(fingerprint)
+            .and_then(|h| h.latency_ms)
+            .map(|ms| format!("{} ms", ms))
+            .unwrap_or_else(|| "untested".to_string());
+
+        let lane_label = match self.lanes.len() {
+            1 => String::new(),
+            _ => format!("lane {} ", lane),
+        };
+        let upstream = self.upstream_config(self.lanes[lane].site.as_deref());
+        let via = upstream
+            .as_ref()
+            .map(|u| format!(" via {}", u.address))
+            .unwrap_or_default();
         info!(
-            "Rotating proxy to node #{} [Protocol: {}] - {}", 
-            self.current_node_index, 
+            "Rotating proxy {}to node #{} [Protocol: {}, Region: {}, Latency: {}] - {} ({}){}",
+            lane_label,
+            index,
             protocol_name,
-            node.address
+            region.unwrap_or("?"),
+            latency,
+            tag,
+            node.address,
+            via
         );
 
-        // 使用 Swapper 一键切换
-        self.swapper.swap_to_node(node);
+        // 使用 Swapper 一键切换 (有前置跳板时为 跳板 -> 节点 两跳链路)
+        let hops: Vec<ClientConfig> = upstream.into_iter().chain([node.clone()]).collect();
+        self.lanes[lane].swapper.swap_to_chain(&hops);
     }
 }
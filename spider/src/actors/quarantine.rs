@@ -0,0 +1,165 @@
+//! 节点隔离 (Per-Site Node Quarantine)
+//!
+//! 记录各站点封禁过的节点指纹及原因，按指数冷却时长暂停该节点在对应站点的使用；
+//! 同一节点对其他站点不受影响。结果持久化于 `cache_path`。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::core::config::QuarantineConfig;
+
+/// 隔离表文件名
+const QUARANTINE_FILE: &str = "proxy_quarantine.json";
+
+/// 单个节点在某站点的隔离记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantineEntry {
+    /// 最近一次封禁原因
+    pub reason: String,
+    /// 累计封禁次数 (决定冷却时长)
+    pub strikes: u32,
+    /// 最近一次封禁时间 (Unix 秒)
+    pub blocked_at: u64,
+    /// 冷却结束时间 (Unix 秒)
+    pub until: u64,
+}
+
+/// 节点隔离表 (站点 -> 节点指纹 -> 记录)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct QuarantineTable {
+    sites: HashMap<String, HashMap<String, QuarantineEntry>>,
+}
+
+impl QuarantineTable {
+    pub fn path(cache_path: &str) -> PathBuf {
+        Path::new(cache_path).join(QUARANTINE_FILE)
+    }
+
+    /// 读取隔离表，文件缺失或损坏时返回空表
+    pub fn load(cache_path: &str) -> Self {
+        std::fs::read(Self::path(cache_path))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, cache_path: &str) {
+        let path = Self::path(cache_path);
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_vec_pretty(self) {
+            Ok(bytes) => {
+                if let Err(e) = std::fs::write(&path, bytes) {
+                    warn!("Failed to save proxy quarantine table: {}", e);
+                }
+            }
+            Err(e) => warn!("Failed to serialize proxy quarantine table: {}", e),
+        }
+    }
+
+    /// 记录一次封禁，返回本次冷却时长 (秒)
+    ///
+    /// 上次冷却结束后又平稳运行超过 `max_secs` 的节点重新从首次冷却计起。
+    pub fn block(
+        &mut self,
+        site: &str,
+        fingerprint: &str,
+        reason: &str,
+        config: &QuarantineConfig,
+        now: u64,
+    ) -> u64 {
+        let entry = self
+            .sites
+            .entry(site.to_string())
+            .or_default()
+            .entry(fingerprint.to_string())
+            .or_insert_with(|| QuarantineEntry {
+                reason: String::new(),
+                strikes: 0,
+                blocked_at: now,
+                until: now,
+            });
+
+        if now.saturating_sub(entry.until) > config.max_secs {
+            entry.strikes = 0;
+        }
+        entry.strikes = entry.strikes.saturating_add(1);
+
+        let exp = (entry.strikes - 1).min(32);
+        let cooldown = config
+            .base_secs
+            .saturating_mul(1u64 << exp)
+            .min(config.max_secs);
+
+        entry.reason = reason.to_string();
+        entry.blocked_at = now;
+        entry.until = now.saturating_add(cooldown);
+        cooldown
+    }
+
+    /// 节点当前是否对该站点处于冷却期
+    pub fn is_quarantined(&self, site: &str, fingerprint: &str, now: u64) -> bool {
+        self.get(site, fingerprint).is_some_and(|e| e.until > now)
+    }
+
+    pub fn get(&self, site: &str, fingerprint: &str) -> Option<&QuarantineEntry> {
+        self.sites.get(site)?.get(fingerprint)
+    }
+
+    /// 节点当前处于冷却期的站点及剩余秒数
+    pub fn active(&self, fingerprint: &str, now: u64) -> Vec<(String, u64)> {
+        let mut sites: Vec<(String, u64)> = self
+            .sites
+            .iter()
+            .filter_map(|(site, nodes)| {
+                let entry = nodes.get(fingerprint).filter(|e| e.until > now)?;
+                Some((site.clone(), entry.until - now))
+            })
+            .collect();
+        sites.sort();
+        sites
+    }
+
+    /// 丢弃已不在节点列表中的记录
+    pub fn retain(&mut self, fingerprints: &[&str]) {
+        for nodes in self.sites.values_mut() {
+            nodes.retain(|fp, _| fingerprints.contains(&fp.as_str()));
+        }
+        self.sites.retain(|_, nodes| !nodes.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cooldown_doubles_per_site() {
+        let config = QuarantineConfig {
+            enabled: true,
+            base_secs: 60,
+            max_secs: 200,
+        };
+        let mut table = QuarantineTable::default();
+
+        assert_eq!(table.block("a", "n1", "IpBlocked(403)", &config, 1000), 60);
+        assert!(table.is_quarantined("a", "n1", 1059));
+        assert!(!table.is_quarantined("a", "n1", 1060));
+        assert!(!table.is_quarantined("b", "n1", 1000));
+        assert_eq!(table.active("n1", 1020), vec![("a".to_string(), 40)]);
+
+        assert_eq!(table.block("a", "n1", "IpBlocked(403)", &config, 1100), 120);
+        assert_eq!(table.block("a", "n1", "IpBlocked(403)", &config, 1300), 200);
+
+        // 冷却结束后长期未再被封，重新计数
+        assert_eq!(
+            table.block("a", "n1", "RateLimit(429)", &config, 10_000),
+            60
+        );
+        assert_eq!(table.get("a", "n1").unwrap().reason, "RateLimit(429)");
+    }
+}
@@ -0,0 +1,136 @@
+//! 验证码样本导出命令 (CAPTCHA Dataset Export)
+//!
+//! 将 `record_samples` 收集的验证码图像与标签整理为训练集，供重新训练本地 OCR 模型。
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::{Context as _, bail};
+
+use crate::network::captcha::{SampleOutcome, SampleRecord, SampleRecorder};
+
+use super::Runtime;
+
+/// 标签文件名
+const LABELS_FILE: &str = "labels.csv";
+
+/// 本地 OCR 识别器名称
+const OCR_SOLVER: &str = "ocr";
+
+/// OCR 未识别正确的样本 (含未经校验者)
+fn is_ocr_failure(record: &SampleRecord) -> bool {
+    match (record.candidate(OCR_SOLVER), record.label()) {
+        (Some(ocr), Some(label)) => ocr.code != label,
+        _ => true,
+    }
+}
+
+/// CSV 字段转义
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 导出站点验证码样本
+///
+/// 图像复制至输出目录并生成 `labels.csv` (file,label,ocr,outcome)；同一图像保留已校验的记录。
+pub async fn export(
+    rt: &Runtime,
+    site_id: String,
+    out: PathBuf,
+    failures_only: bool,
+) -> anyhow::Result<()> {
+    if !rt.registry.list().contains(&site_id.as_str()) {
+        bail!(
+            "Unknown site identifier: {} (available: {})",
+            site_id,
+            rt.registry.list().join(", ")
+        );
+    }
+
+    let dir = SampleRecorder::site_dir(&rt.config.cache_path, &site_id);
+    let records = SampleRecorder::load(&dir)?;
+    if records.is_empty() {
+        bail!(
+            "No CAPTCHA samples in {} (enable sites.{}.captcha.record_samples)",
+            dir.display(),
+            site_id
+        );
+    }
+
+    // 同一图像优先保留已校验通过的记录
+    let mut samples: HashMap<&str, &SampleRecord> = HashMap::new();
+    for record in &records {
+        let keep = samples
+            .get(record.file.as_str())
+            .is_none_or(|prev| prev.label().is_none());
+        if keep {
+            samples.insert(&record.file, record);
+        }
+    }
+
+    std::fs::create_dir_all(&out).with_context(|| format!("Failed to create {}", out.display()))?;
+
+    let mut files: Vec<_> = samples.into_values().collect();
+    files.sort_by(|a, b| a.file.cmp(&b.file));
+
+    let mut csv = String::from("file,label,ocr,outcome\n");
+    let mut exported = 0;
+    let mut labeled = 0;
+    for record in files {
+        if failures_only && !is_ocr_failure(record) {
+            continue;
+        }
+
+        let src = dir.join(&record.file);
+        if let Err(e) = std::fs::copy(&src, out.join(&record.file)) {
+            tracing::warn!("Skipping {}: {}", src.display(), e);
+            continue;
+        }
+
+        let outcome = match record.outcome {
+            SampleOutcome::Accepted => "accepted",
+            SampleOutcome::Rejected => "rejected",
+            SampleOutcome::Unsolved => "unsolved",
+        };
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            csv_field(&record.file),
+            csv_field(record.label().unwrap_or_default()),
+            csv_field(
+                record
+                    .candidate(OCR_SOLVER)
+                    .map(|c| c.code.as_str())
+                    .unwrap_or_default()
+            ),
+            outcome
+        ));
+
+        exported += 1;
+        if record.label().is_some() {
+            labeled += 1;
+        }
+    }
+
+    std::fs::write(out.join(LABELS_FILE), csv)
+        .with_context(|| format!("Failed to write {}", LABELS_FILE))?;
+
+    let count = |o: SampleOutcome| records.iter().filter(|r| r.outcome == o).count();
+    println!(
+        "Records: {} (accepted {}, rejected {}, unsolved {})",
+        records.len(),
+        count(SampleOutcome::Accepted),
+        count(SampleOutcome::Rejected),
+        count(SampleOutcome::Unsolved)
+    );
+    println!(
+        "Exported {} images ({} labeled) to {}",
+        exported,
+        labeled,
+        out.display()
+    );
+    Ok(())
+}
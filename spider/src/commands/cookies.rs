@@ -0,0 +1,176 @@
+//! 凭据导入导出命令 (Cookies Command)
+//!
+//! 在自动化绕过失败时，导入操作员在桌面浏览器中获得的会话凭据；或导出当前凭据供其他工具复用。
+
+use std::path::PathBuf;
+
+use anyhow::{Context as _, bail};
+use clap::ValueEnum;
+
+use crate::actors::proxy::ProxyState;
+use crate::core::config::AppConfig;
+use crate::network::cookie::{CookieLocation, CookieSpec, DIRECT_NODE};
+use crate::network::session::Session;
+use crate::sites::SiteRegistry;
+use crate::utils::cookie_file;
+
+/// 凭据文件格式
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum CookieFormat {
+    /// Netscape `cookies.txt`
+    #[default]
+    Netscape,
+    /// EditThisCookie 风格 JSON 数组
+    Json,
+}
+
+/// 派生绑定至目标站点凭据存储的会话
+///
+/// 未指定节点时使用 `proxy_state.json` 中最近使用的节点 (不启动代理 Actor，避免轮换)；
+/// `direct` 表示直连。站点与节点名称均用作路径片段，仅接受已注册的站点标识与十六进制指纹。
+/// 返回的布尔值表示节点是否取自持久化状态。
+fn bind(
+    config: &AppConfig,
+    site_id: &str,
+    node: Option<String>,
+) -> anyhow::Result<(Session, bool)> {
+    let registry = SiteRegistry::new();
+    let safe_site = site_id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !safe_site || !registry.list().contains(&site_id) {
+        bail!(
+            "Unknown site identifier: {} (available: {})",
+            site_id,
+            registry.list().join(", ")
+        );
+    }
+
+    let (node, from_state) = match node {
+        Some(node) if !CookieLocation::is_valid_node(&node) => bail!(
+            "Invalid node '{}', expected a hex node fingerprint or '{}'",
+            node,
+            DIRECT_NODE
+        ),
+        Some(node) => ((node != DIRECT_NODE).then_some(node), false),
+        None => {
+            let node = last_node(config)?;
+            let from_state = node.is_some();
+            (node, from_state)
+        }
+    };
+    let session = Session::new();
+    session.attach(CookieLocation::new(&config.cache_path, site_id, node));
+    Ok((session, from_state))
+}
+
+/// 最近一次抓取使用的主通道节点 (代理未启用时为直连)
+fn last_node(config: &AppConfig) -> anyhow::Result<Option<String>> {
+    if !config.proxy.enabled || !config.proxy.has_node_sources() {
+        return Ok(None);
+    }
+    match ProxyState::load(&config.cache_path) {
+        Some(state) => Ok(Some(state.last_node_fingerprint)),
+        None => bail!(
+            "No proxy node has been used yet, specify one with --node <FINGERPRINT> (or --node {})",
+            DIRECT_NODE
+        ),
+    }
+}
+
+/// 导入凭据文件 (格式自动识别) 并写入站点会话
+pub fn import(
+    config: &AppConfig,
+    site_id: String,
+    file: PathBuf,
+    ua: Option<String>,
+    node: Option<String>,
+) -> anyhow::Result<()> {
+    let text = std::fs::read_to_string(&file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let specs = cookie_file::parse_cookies(&text)?;
+    let total = specs.len();
+
+    let (session, from_state) = bind(config, &site_id, node)?;
+    let imported = specs
+        .into_iter()
+        .filter(|spec| session.import_cookie(spec.clone()))
+        .count();
+
+    if let Some(ua) = ua {
+        session.set_ua(ua);
+    }
+    session.persist()?;
+
+    // 下次抓取沿用该节点而非跳过，使导入的凭据生效
+    if from_state && let Some(mut state) = ProxyState::load(&config.cache_path) {
+        state.resume = true;
+        state.save(&config.cache_path);
+    }
+
+    println!(
+        "Imported {}/{} cookies into {} (User-Agent: {})",
+        imported,
+        total,
+        site_id,
+        session.get_ua()
+    );
+    Ok(())
+}
+
+/// 导出站点当前凭据，`-` 表示输出至标准输出
+pub fn export(
+    config: &AppConfig,
+    site_id: String,
+    file: PathBuf,
+    format: CookieFormat,
+    node: Option<String>,
+) -> anyhow::Result<()> {
+    let (session, _) = bind(config, &site_id, node)?;
+    let specs: Vec<CookieSpec> = session
+        .cookies()
+        .iter()
+        .filter_map(CookieSpec::from_stored)
+        .collect();
+
+    let output = match format {
+        CookieFormat::Netscape => cookie_file::to_netscape(&specs),
+        CookieFormat::Json => cookie_file::to_json(&specs)?,
+    };
+
+    if file.as_os_str() == "-" {
+        print!("{}", output);
+    } else {
+        std::fs::write(&file, output)
+            .with_context(|| format!("Failed to write {}", file.display()))?;
+        eprintln!(
+            "Exported {} cookies to {} (User-Agent: {})",
+            specs.len(),
+            file.display(),
+            session.get_ua()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_path_like_site_and_node() {
+        let config = AppConfig::default();
+
+        for node in ["../../x", "abc/def", "", "direct.json"] {
+            let err = bind(&config, "booktoki", Some(node.to_string()))
+                .map(|_| ())
+                .unwrap_err();
+            assert!(err.to_string().contains("Invalid node"), "{}", err);
+        }
+        let err = bind(&config, "../booktoki", None).map(|_| ()).unwrap_err();
+        assert!(err.to_string().contains("Unknown site"), "{}", err);
+
+        assert!(bind(&config, "booktoki", Some(DIRECT_NODE.to_string())).is_ok());
+        assert!(bind(&config, "booktoki", Some("0f3a9c".to_string())).is_ok());
+    }
+}
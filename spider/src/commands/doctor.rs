@@ -0,0 +1,581 @@
+//! 诊断命令 (Doctor Command)
+//!
+//! 不指定站点时检查运行环境 (配置、订阅、前置跳板、代理节点、浏览器与缓存目录)；
+//! 指定站点时以已知作品检查站点适配的选择器是否仍然有效，适合定时运行以尽早发现站点改版。
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::bail;
+use shoes::resolver::{NativeResolver, Resolver};
+use tokio::sync::oneshot;
+use url::Url;
+
+use crate::actors::health;
+use crate::actors::proxy::{ProxyManager, ProxyMsg};
+use crate::core::config::AppConfig;
+use crate::core::model::Check;
+use crate::interfaces::site::{Context, TaskArgs};
+use crate::network::browser::{BrowserService, find_chrome_executable};
+use crate::network::egress;
+use crate::sites::SiteRegistry;
+use crate::utils::node_filter::NodeFilter;
+use crate::utils::subscription::{
+    ProxyNode, fetch_subscription, load_local_nodes, local_source_path, parse_proxy_uri,
+    parse_subscription_content, parse_upstream, subscription_client,
+};
+
+use super::Runtime;
+
+/// 配置文件路径
+const CONFIG_FILE: &str = "config.toml";
+/// 端到端探测地址
+const PROBE_URL: &str = "https://www.gstatic.com/generate_204";
+/// 端到端探测的节点数量上限
+const PROBE_SAMPLE: usize = 5;
+/// 单次探测超时
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+/// 等待代理核心就绪 (含订阅下载) 的超时
+const PROXY_READY_TIMEOUT: Duration = Duration::from_secs(60);
+/// 浏览器启动超时
+const BROWSER_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 执行运行环境检查，任一检查项未通过时返回错误 (非零退出)
+pub async fn env() -> anyhow::Result<()> {
+    let mut checks = Vec::new();
+
+    let Some(config) = check_config(&mut checks) else {
+        return report(&checks);
+    };
+    let config = Arc::new(config);
+
+    check_cache_dir(&config, &mut checks);
+    check_subscriptions(&config, &mut checks).await;
+    check_upstreams(&config, &mut checks).await;
+    if check_port(&config, &mut checks) {
+        check_proxy_nodes(&config, &mut checks).await;
+    }
+    check_browser(&config, &mut checks).await;
+
+    report(&checks)
+}
+
+/// 加载并校验配置文件
+fn check_config(checks: &mut Vec<Check>) -> Option<AppConfig> {
+    const STAGE: &str = "config";
+
+    if !Path::new(CONFIG_FILE).exists() {
+        checks.push(Check::info(
+            STAGE,
+            "file",
+            format!("{} not found, default created", CONFIG_FILE),
+        ));
+    }
+
+    let config = match AppConfig::load() {
+        Ok(config) => {
+            checks.push(Check::new(
+                STAGE,
+                "parse",
+                format!("{} parsed", CONFIG_FILE),
+                true,
+            ));
+            config
+        }
+        Err(e) => {
+            checks.push(Check::new(STAGE, "parse", e.to_string(), false));
+            return None;
+        }
+    };
+
+    for (i, url) in config.proxy.subscription_urls.iter().enumerate() {
+        let valid = local_source_path(url).is_some()
+            || Url::parse(url).is_ok_and(|u| matches!(u.scheme(), "http" | "https"));
+        if !valid {
+            checks.push(Check::new(
+                STAGE,
+                "subscription_urls",
+                format!("entry #{} is not an http(s) or file:// URL", i + 1),
+                false,
+            ));
+        }
+    }
+    for (i, uri) in config.proxy.static_nodes.iter().enumerate() {
+        if parse_proxy_uri(uri.trim()).is_none() {
+            checks.push(Check::new(
+                STAGE,
+                "static_nodes",
+                format!("entry #{} is not a supported proxy URI", i + 1),
+                false,
+            ));
+        }
+    }
+    if let Some(dir) = &config.proxy.node_dir
+        && !Path::new(dir).is_dir()
+    {
+        checks.push(Check::new(
+            STAGE,
+            "node_dir",
+            format!("{} is not a directory", dir),
+            false,
+        ));
+    }
+
+    let registry = SiteRegistry::new();
+    for (site_id, site) in &config.sites {
+        if !registry.list().contains(&site_id.as_str()) {
+            checks.push(Check::new(
+                STAGE,
+                "sites",
+                format!(
+                    "[sites.{}] does not match any site (available: {})",
+                    site_id,
+                    registry.list().join(", ")
+                ),
+                false,
+            ));
+        }
+        if let Some(base) = &site.base_url
+            && let Err(e) = Url::parse(base)
+        {
+            checks.push(Check::new(
+                STAGE,
+                "base_url",
+                format!("sites.{}.base_url: {}", site_id, e),
+                false,
+            ));
+        }
+    }
+
+    Some(config)
+}
+
+/// 缓存目录可写
+fn check_cache_dir(config: &AppConfig, checks: &mut Vec<Check>) {
+    let dir = Path::new(&config.cache_path);
+    let probe = dir.join(".doctor");
+    let result = std::fs::create_dir_all(dir)
+        .and_then(|_| std::fs::write(&probe, b"ok"))
+        .and_then(|_| std::fs::remove_file(&probe));
+
+    checks.push(match result {
+        Ok(_) => Check::new("cache", "writable", dir.display().to_string(), true),
+        Err(e) => Check::new(
+            "cache",
+            "writable",
+            format!("{}: {}", dir.display(), e),
+            false,
+        ),
+    });
+}
+
+/// 下载并解析订阅与本地节点来源，统计可转换为 shoes 配置的节点
+///
+/// 订阅地址常含令牌，输出中仅显示主机名。
+async fn check_subscriptions(config: &AppConfig, checks: &mut Vec<Check>) {
+    const STAGE: &str = "subscriptions";
+
+    if !config.proxy.enabled {
+        checks.push(Check::info(
+            STAGE,
+            "mode",
+            "proxy disabled, direct connection",
+        ));
+        return;
+    }
+    if !config.proxy.has_node_sources() {
+        checks.push(Check::info(
+            STAGE,
+            "mode",
+            "no subscription URLs or static nodes, direct connection",
+        ));
+        return;
+    }
+
+    let filter = match NodeFilter::new(&config.proxy.filter) {
+        Ok(filter) => filter,
+        Err(e) => {
+            checks.push(Check::new(STAGE, "filter", format!("{:#}", e), false));
+            return;
+        }
+    };
+
+    let client = match subscription_client() {
+        Ok(client) => client,
+        Err(e) => {
+            checks.push(Check::new(STAGE, "client", e.to_string(), false));
+            return;
+        }
+    };
+
+    for (i, url) in config.proxy.subscription_urls.iter().enumerate() {
+        let name = format!("#{}", i + 1);
+        let host = match local_source_path(url) {
+            Some(path) => path.display().to_string(),
+            None => Url::parse(url)
+                .ok()
+                .and_then(|u| u.host_str().map(str::to_string))
+                .unwrap_or_else(|| "(invalid)".into()),
+        };
+
+        let nodes = match fetch_subscription(&client, url)
+            .await
+            .and_then(|content| parse_subscription_content(&content))
+        {
+            Ok(nodes) => nodes,
+            Err(e) => {
+                checks.push(Check::new(
+                    STAGE,
+                    &name,
+                    redact(&format!("{:#}", e), url, &host),
+                    false,
+                ));
+                continue;
+            }
+        };
+
+        check_nodes(checks, &filter, &name, &host, &nodes);
+    }
+
+    if !config.proxy.static_nodes.is_empty() || config.proxy.node_dir.is_some() {
+        let dir = config.proxy.node_dir.as_deref().map(Path::new);
+        let nodes = load_local_nodes(&config.proxy.static_nodes, &[], dir).await;
+        let label = match dir {
+            Some(dir) => format!("static_nodes + {}", dir.display()),
+            None => "static_nodes".to_string(),
+        };
+        check_nodes(checks, &filter, "static", &label, &nodes);
+    }
+}
+
+/// 统计一个节点来源中通过筛选并可转换的节点，按原因汇总被跳过的节点
+fn check_nodes(
+    checks: &mut Vec<Check>,
+    filter: &NodeFilter,
+    name: &str,
+    label: &str,
+    nodes: &[ProxyNode],
+) {
+    const STAGE: &str = "subscriptions";
+
+    let mut converted = 0;
+    let mut skipped: HashMap<String, usize> = HashMap::new();
+    for node in nodes {
+        if let Err(reason) = filter.check(node) {
+            *skipped.entry(format!("filtered, {}", reason)).or_default() += 1;
+            continue;
+        }
+        match node.to_shoes_config() {
+            Ok(_) => converted += 1,
+            Err(e) => *skipped.entry(e.to_string()).or_default() += 1,
+        }
+    }
+
+    checks.push(Check::new(
+        STAGE,
+        name,
+        format!(
+            "{}: {} node(s), {} usable after filtering and conversion",
+            label,
+            nodes.len(),
+            converted
+        ),
+        converted > 0,
+    ));
+
+    let mut skipped: Vec<_> = skipped.into_iter().collect();
+    skipped.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    for (reason, count) in skipped {
+        checks.push(Check::info(
+            STAGE,
+            "skipped",
+            format!("{} node(s): {}", count, reason),
+        ));
+    }
+}
+
+/// 将错误信息中的订阅地址替换为主机名
+fn redact(message: &str, url: &str, host: &str) -> String {
+    let normalized = Url::parse(url).map(|u| u.to_string()).unwrap_or_default();
+    [url, normalized.as_str()]
+        .into_iter()
+        .filter(|full| !full.is_empty())
+        .fold(message.to_string(), |acc, full| acc.replace(full, host))
+}
+
+/// 各出口通道的本地代理端口均未被占用
+fn check_port(config: &AppConfig, checks: &mut Vec<Check>) -> bool {
+    (0..egress::count(config)).all(|lane| {
+        let port = egress::port(config, lane);
+        match std::net::TcpListener::bind(("127.0.0.1", port)) {
+            Ok(_) => {
+                checks.push(Check::new(
+                    "proxy",
+                    "port",
+                    format!("127.0.0.1:{} available", port),
+                    true,
+                ));
+                true
+            }
+            Err(e) => {
+                checks.push(Check::new(
+                    "proxy",
+                    "port",
+                    format!("127.0.0.1:{}: {} (another instance running?)", port, e),
+                    false,
+                ));
+                false
+            }
+        }
+    })
+}
+
+/// 单独经各前置跳板请求健康检查地址，区分跳板故障与节点故障
+///
+/// 节点探测 (本地端口与健康检查) 均经 跳板 -> 节点 链路进行。
+async fn check_upstreams(config: &AppConfig, checks: &mut Vec<Check>) {
+    const STAGE: &str = "upstream";
+
+    if !config.proxy.enabled {
+        return;
+    }
+    let mut upstreams: Vec<(String, &str)> = Vec::new();
+    if let Some(uri) = config.proxy.upstream_for(None) {
+        upstreams.push(("proxy".into(), uri));
+    }
+    let mut sites: Vec<_> = config.sites.iter().collect();
+    sites.sort_by_key(|(id, _)| id.as_str());
+    for (id, site) in sites {
+        match config.proxy.upstream_for(Some(site)) {
+            Some(uri) if site.upstream.is_some() => upstreams.push((format!("sites.{}", id), uri)),
+            None if config.proxy.upstream.is_some() => {
+                checks.push(Check::info(
+                    STAGE,
+                    &format!("sites.{}", id),
+                    "disabled, nodes reached directly",
+                ));
+            }
+            _ => {}
+        }
+    }
+    if upstreams.is_empty() {
+        return;
+    }
+
+    let url = match Url::parse(&config.proxy.health.url) {
+        Ok(url) if url.scheme() == "http" => url,
+        _ => {
+            checks.push(Check::new(
+                STAGE,
+                "probe",
+                "proxy.health.url must be an http:// URL to probe upstreams",
+                false,
+            ));
+            return;
+        }
+    };
+    let resolver: Arc<dyn Resolver> = Arc::new(NativeResolver::new());
+
+    for (name, uri) in upstreams {
+        // 配置加载时已校验，此处失败仅在测试配置中出现
+        let upstream = match parse_upstream(uri) {
+            Ok(upstream) => upstream,
+            Err(e) => {
+                checks.push(Check::new(STAGE, &name, format!("{:#}", e), false));
+                continue;
+            }
+        };
+        let target = format!("{} {}", upstream.protocol.protocol_name(), upstream.address);
+        let result = health::probe_node(
+            std::slice::from_ref(&upstream),
+            resolver.clone(),
+            &url,
+            PROBE_TIMEOUT,
+        )
+        .await;
+        checks.push(match result {
+            Ok(latency) => Check::new(
+                STAGE,
+                &name,
+                format!("{} reachable in {} ms", target, latency.as_millis()),
+                true,
+            ),
+            Err(e) => Check::new(STAGE, &name, format!("{}: {:#}", target, e), false),
+        });
+    }
+}
+
+/// 经本地代理端口对若干节点做端到端探测
+async fn check_proxy_nodes(config: &Arc<AppConfig>, checks: &mut Vec<Check>) {
+    const STAGE: &str = "proxy";
+
+    let (proxy_tx, proxy_handle) = ProxyManager::start(config.clone()).await;
+
+    let proxy_url = format!("http://127.0.0.1:{}", config.proxy.proxy_port);
+    let client = match reqwest::Proxy::all(proxy_url).and_then(|proxy| {
+        reqwest::Client::builder()
+            .proxy(proxy)
+            .timeout(PROBE_TIMEOUT)
+            .build()
+    }) {
+        Ok(client) => client,
+        Err(e) => {
+            checks.push(Check::new(STAGE, "client", e.to_string(), false));
+            return;
+        }
+    };
+
+    let mut seen = Vec::new();
+    let mut probed = 0;
+    let mut reachable = 0;
+    for _ in 0..PROBE_SAMPLE {
+        // 代理核心在节点加载并完成监听后才响应指令
+        let (tx, rx) = oneshot::channel();
+        if proxy_tx.send(ProxyMsg::Current { lane: 0, reply: tx }).is_err() {
+            break;
+        }
+        let current = match tokio::time::timeout(PROXY_READY_TIMEOUT, rx).await {
+            Ok(Ok(current)) => current,
+            _ => break,
+        };
+
+        let label = match &current {
+            Some(fp) if seen.contains(fp) => break,
+            Some(fp) => format!("node {}", &fp[..8.min(fp.len())]),
+            None => "direct".to_string(),
+        };
+
+        probed += 1;
+        let start = Instant::now();
+        let detail = match client.get(PROBE_URL).send().await {
+            Ok(resp) if resp.status().is_success() => {
+                reachable += 1;
+                format!(
+                    "HTTP {} in {} ms",
+                    resp.status().as_u16(),
+                    start.elapsed().as_millis()
+                )
+            }
+            Ok(resp) => format!("failed: HTTP {}", resp.status().as_u16()),
+            Err(e) => format!("failed: {}", e),
+        };
+        checks.push(Check::info(STAGE, &label, detail));
+
+        let Some(fp) = current else {
+            break;
+        };
+        seen.push(fp);
+
+        let (tx, rx) = oneshot::channel();
+        let msg = ProxyMsg::Rotate {
+            lane: 0,
+            site: None,
+            reason: None,
+            reply: Some(tx),
+        };
+        if proxy_tx.send(msg).is_err() || rx.await.is_err() {
+            break;
+        }
+    }
+
+    checks.push(if probed == 0 {
+        Check::new(
+            STAGE,
+            "reachable",
+            "local proxy did not become ready",
+            false,
+        )
+    } else {
+        Check::new(
+            STAGE,
+            "reachable",
+            format!("{}/{} via {}", reachable, probed, PROBE_URL),
+            reachable > 0,
+        )
+    });
+
+    proxy_handle.abort();
+}
+
+/// 浏览器可执行文件存在且能够启动
+async fn check_browser(config: &Arc<AppConfig>, checks: &mut Vec<Check>) {
+    const STAGE: &str = "browser";
+
+    let Some(path) = find_chrome_executable(config) else {
+        checks.push(Check::new(
+            STAGE,
+            "executable",
+            "Chrome/Chromium not found (set browser.chrome_path or CHROME_PATH)",
+            false,
+        ));
+        return;
+    };
+    checks.push(Check::new(
+        STAGE,
+        "executable",
+        path.display().to_string(),
+        true,
+    ));
+
+    let browser = BrowserService::new(config.clone());
+    let pool = browser.pool();
+    match tokio::time::timeout(BROWSER_TIMEOUT, pool.acquire()).await {
+        Ok(Ok(page)) => {
+            pool.discard(page).await;
+            checks.push(Check::new(STAGE, "launch", "browser launched", true));
+        }
+        Ok(Err(e)) => checks.push(Check::new(STAGE, "launch", e.to_string(), false)),
+        Err(_) => checks.push(Check::new(STAGE, "launch", "timed out", false)),
+    }
+    pool.shutdown().await;
+}
+
+/// 执行站点健康检查，任一检查项未通过时返回错误 (非零退出)
+pub async fn site(rt: &Runtime, site_id: String, id: String) -> anyhow::Result<()> {
+    let ctx = rt.service_context();
+    let Some(site) = rt.create_site(&site_id, ctx.clone()).await else {
+        bail!("Unknown site identifier: {}", site_id);
+    };
+
+    let mut args = TaskArgs::new();
+    args.insert("id".to_string(), id.clone());
+    let site_ctx = Context::new(id, args, ctx.clone());
+
+    if let Err(e) = site.prepare(&site_ctx).await {
+        tracing::warn!("Site preparation warning: {}", e);
+    }
+
+    let checks = site.diagnose(&site_ctx).await;
+    ctx.persist_session();
+    report(&checks?)
+}
+
+/// 按阶段输出检查结果并汇总
+fn report(checks: &[Check]) -> anyhow::Result<()> {
+    let mut stage = "";
+    for check in checks {
+        if check.stage != stage {
+            stage = &check.stage;
+            println!("\n[{}]", stage);
+        }
+        println!(
+            "  {} {:<18} {}",
+            match (check.advisory, check.passed) {
+                (true, _) => "INFO",
+                (false, true) => "PASS",
+                (false, false) => "FAIL",
+            },
+            check.name,
+            check.detail
+        );
+    }
+
+    let failed = checks.iter().filter(|c| !c.passed).count();
+    println!("\n{} check(s), {} failed", checks.len(), failed);
+    if failed > 0 {
+        bail!("{} of {} checks failed", failed, checks.len());
+    }
+    Ok(())
+}
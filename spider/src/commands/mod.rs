@@ -0,0 +1,91 @@
+//! 命令处理器 (Command Handlers)
+//!
+//! 各子命令的执行逻辑，以及其共享的运行时依赖装配。
+
+pub mod captcha;
+pub mod cookies;
+pub mod doctor;
+pub mod proxy;
+pub mod scrape;
+pub mod search;
+
+use std::sync::Arc;
+
+use flume::Sender;
+use tokio::task::JoinHandle;
+
+use crate::actors::control::ControlServer;
+use crate::actors::proxy::{ProxyManager, ProxyMsg};
+use crate::core::config::AppConfig;
+use crate::interfaces::Site;
+use crate::network::browser::BrowserService;
+use crate::network::context::ServiceContext;
+use crate::network::service::HttpService;
+use crate::sites::SiteRegistry;
+
+/// 共享运行时依赖 (Shared Runtime Dependencies)
+///
+/// 聚合代理 Actor、HTTP 引擎、浏览器服务及站点注册表。
+pub struct Runtime {
+    pub config: Arc<AppConfig>,
+    pub proxy_tx: Sender<ProxyMsg>,
+    pub proxy_handle: JoinHandle<()>,
+    pub http: Arc<HttpService>,
+    pub browser: Arc<BrowserService>,
+    pub registry: SiteRegistry,
+}
+
+impl Runtime {
+    /// 启动代理 Actor 并装配网络栈
+    pub async fn bootstrap(config: Arc<AppConfig>) -> Self {
+        let (proxy_tx, proxy_handle) = ProxyManager::start(config.clone()).await;
+        let http = Arc::new(HttpService::new(config.clone()));
+        let browser = Arc::new(BrowserService::new(config.clone()));
+
+        Self {
+            config,
+            proxy_tx,
+            proxy_handle,
+            http,
+            browser,
+            registry: SiteRegistry::new(),
+        }
+    }
+
+    /// 开放代理控制通道，供 `spider proxy` 子命令查询与控制本进程的代理
+    pub async fn serve_control(&self) -> Option<ControlServer> {
+        ControlServer::start(&self.config.cache_path, self.proxy_tx.clone())
+            .await
+            .inspect_err(|e| tracing::warn!("Failed to open proxy control channel: {:#}", e))
+            .ok()
+    }
+
+    /// 派生携带独立会话的服务上下文
+    pub fn service_context(&self) -> ServiceContext {
+        ServiceContext::new(
+            self.http.clone(),
+            self.proxy_tx.clone(),
+            self.browser.clone(),
+            self.config.clone(),
+        )
+    }
+
+    /// 按标识符实例化站点，并恢复该站点持久化的会话凭据
+    pub async fn create_site(&self, site_id: &str, ctx: ServiceContext) -> Option<Arc<dyn Site>> {
+        let site_cfg = self.config.sites.get(site_id).cloned().unwrap_or_default();
+        match self.registry.create(site_id, site_cfg, ctx.clone()) {
+            Some(s) => {
+                ctx.bind_site(site_id).await;
+                Some(Arc::from(s))
+            }
+            None => {
+                tracing::error!(
+                    "Unknown site identifier: {} (available: {})",
+                    site_id,
+                    self.registry.list().join(", ")
+                );
+                None
+            }
+        }
+    }
+}
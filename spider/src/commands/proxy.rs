@@ -0,0 +1,273 @@
+//! 代理管理命令 (Proxy Management)
+//!
+//! 查看节点状态、探测与固定节点，以及执行订阅刷新等维护操作。
+//! 有抓取进程运行时经控制通道作用于其代理；否则在本进程内离线加载节点，
+//! 查询不会轮换节点或改写 `proxy_state.json`。
+
+use std::sync::Arc;
+
+use anyhow::bail;
+use flume::Sender;
+use serde::de::DeserializeOwned;
+
+use crate::actors::control::{self, ControlClient, ControlRequest};
+use crate::actors::health::NodeHealth;
+use crate::actors::proxy::{NodeInfo, ProxyManager, ProxyMsg, ProxyStatus, RefreshSummary};
+use crate::core::config::AppConfig;
+use crate::network::egress;
+
+/// 代理连接
+enum Link {
+    /// 正在运行的抓取进程
+    Running(ControlClient),
+    /// 本进程内的离线代理管理器
+    Detached(Sender<ProxyMsg>),
+}
+
+impl Link {
+    /// 优先连接正在运行的抓取进程，否则启动离线代理管理器
+    async fn open(config: &Arc<AppConfig>) -> Self {
+        match ControlClient::connect(&config.cache_path).await {
+            Some(client) => Self::Running(client),
+            None => Self::Detached(ProxyManager::start_detached(config.clone()).0),
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        matches!(self, Self::Running(_))
+    }
+
+    async fn call<T: DeserializeOwned>(&self, request: ControlRequest) -> anyhow::Result<T> {
+        match self {
+            Self::Running(client) => client.call(request).await,
+            Self::Detached(tx) => Ok(serde_json::from_value(
+                control::dispatch(tx, request).await?,
+            )?),
+        }
+    }
+}
+
+/// 连接正在运行的抓取进程 (暂停与恢复仅对运行中的代理有意义)
+async fn running(config: &AppConfig, action: &str) -> anyhow::Result<ControlClient> {
+    match ControlClient::connect(&config.cache_path).await {
+        Some(client) => Ok(client),
+        None => bail!("No running scrape to {}", action),
+    }
+}
+
+/// 忽略缓存重新拉取订阅，合并后写回 `sub_cache.json`
+pub async fn refresh(config: &Arc<AppConfig>) -> anyhow::Result<()> {
+    let link = Link::open(config).await;
+    let summary: RefreshSummary = link.call(ControlRequest::Refresh).await?;
+
+    println!(
+        "Subscription refreshed: {} nodes ({} added, {} removed)",
+        summary.total, summary.added, summary.removed
+    );
+    if summary.kept_current {
+        println!(
+            "Current node is no longer in the subscription and is kept until the next rotation"
+        );
+    }
+    Ok(())
+}
+
+/// 列出节点及其协议、地区、健康状况与隔离状态
+pub async fn list(config: &Arc<AppConfig>) -> anyhow::Result<()> {
+    let link = Link::open(config).await;
+    let nodes: Vec<NodeInfo> = link.call(ControlRequest::List).await?;
+    if nodes.is_empty() {
+        println!("No proxy nodes loaded (direct connection).");
+        return Ok(());
+    }
+
+    println!(
+        "  {:>4}  {:<12}  {:<6}  {:<12}  {:<24}  TAG",
+        "#", "PROTOCOL", "REGION", "HEALTH", "QUARANTINE"
+    );
+    for node in &nodes {
+        let quarantine = node
+            .quarantined
+            .iter()
+            .map(|(site, secs)| format!("{} {}", site, format_secs(*secs)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!(
+            "{} {:>4}  {:<12}  {:<6}  {:<12}  {:<24}  {}",
+            match (node.current, node.lanes.is_empty()) {
+                (true, _) => "*",
+                (false, false) => "+",
+                (false, true) => " ",
+            },
+            node.index,
+            node.protocol,
+            node.region.as_deref().unwrap_or("-"),
+            health_label(node.health.as_ref()),
+            if quarantine.is_empty() {
+                "-".to_string()
+            } else {
+                quarantine
+            },
+            node.tag
+        );
+    }
+    println!();
+    if link.is_running() {
+        println!("* current node, + used by another egress lane");
+    } else {
+        println!("* last used node (no scrape running)");
+    }
+    println!("Pin one with: spider proxy use <TAG|INDEX>");
+    Ok(())
+}
+
+/// 立即探测节点 (缺省为全部节点)，全部失败时返回错误
+pub async fn test(config: &Arc<AppConfig>, node: Option<String>) -> anyhow::Result<()> {
+    let link = Link::open(config).await;
+    let nodes: Vec<NodeInfo> = link.call(ControlRequest::Test { query: node }).await?;
+
+    let mut passed = 0;
+    for node in &nodes {
+        let result = match &node.health {
+            Some(h) if h.ok => {
+                passed += 1;
+                format!("ok    {}", health_label(Some(h)))
+            }
+            Some(h) => format!(
+                "FAIL  {}",
+                h.last_error.as_deref().unwrap_or("unknown error")
+            ),
+            None => "FAIL  not probed".to_string(),
+        };
+        println!("{:>4}  {:<40}  {}", node.index, node.tag, result);
+    }
+    println!();
+    println!("{}/{} node(s) passed", passed, nodes.len());
+
+    if passed == 0 {
+        bail!("No proxy node passed the probe");
+    }
+    Ok(())
+}
+
+/// 固定使用指定节点；`node` 为 `None` 时解除固定
+pub async fn use_node(config: &Arc<AppConfig>, node: Option<String>) -> anyhow::Result<()> {
+    let link = Link::open(config).await;
+    let pinned: Option<NodeInfo> = link.call(ControlRequest::Use { query: node }).await?;
+
+    match pinned {
+        Some(node) => {
+            println!("Pinned node #{}: {}", node.index, describe(&node));
+            println!("Automatic rotation is disabled until: spider proxy use --auto");
+        }
+        None => println!("Node unpinned, automatic rotation resumed."),
+    }
+    if !link.is_running() {
+        println!("No scrape running, the change applies when the next scrape starts.");
+    }
+    Ok(())
+}
+
+/// 暂停正在运行的抓取进程的自动轮换，各通道保持当前节点
+pub async fn pause(config: &Arc<AppConfig>) -> anyhow::Result<()> {
+    running(config, "pause")
+        .await?
+        .call::<()>(ControlRequest::Pause)
+        .await?;
+    println!("Proxy rotation paused, current nodes are kept until: spider proxy resume");
+    Ok(())
+}
+
+/// 恢复正在运行的抓取进程的自动轮换
+pub async fn resume(config: &Arc<AppConfig>) -> anyhow::Result<()> {
+    running(config, "resume")
+        .await?
+        .call::<()>(ControlRequest::Resume)
+        .await?;
+    println!("Proxy rotation resumed.");
+    Ok(())
+}
+
+/// 显示代理运行状态与当前出口节点
+pub async fn status(config: &Arc<AppConfig>) -> anyhow::Result<()> {
+    let link = Link::open(config).await;
+    let status: ProxyStatus = link.call(ControlRequest::Status).await?;
+    if !status.enabled {
+        println!("Proxy disabled (proxy.enabled = false), all traffic goes direct.");
+        return Ok(());
+    }
+
+    if !link.is_running() {
+        println!("Scrape:  not running (showing the last used node)");
+    }
+    println!("Nodes:   {} loaded, {} usable", status.total, status.usable);
+    match &status.current {
+        Some(node) => {
+            let label = if link.is_running() { "Current" } else { "Last" };
+            println!(
+                "{:<8} #{} {}",
+                format!("{}:", label),
+                node.index,
+                describe(node)
+            );
+            println!("Health:  {}", health_label(node.health.as_ref()));
+            println!(
+                "Mode:    {}",
+                match (status.paused, status.pinned) {
+                    (true, _) => "paused (no rotation or probing)",
+                    (false, true) => "pinned",
+                    (false, false) => "automatic rotation",
+                }
+            );
+        }
+        None => println!("Current: direct connection (no proxy nodes loaded)"),
+    }
+    if let Some(upstream) = &status.upstream {
+        println!("Via:     {} (upstream)", upstream);
+    }
+    if status.lanes.len() > 1 {
+        println!();
+        for (lane, node) in status.lanes.iter().enumerate() {
+            println!(
+                "Lane {} (127.0.0.1:{}): #{} {}",
+                lane,
+                egress::port(config, lane),
+                node.index,
+                describe(node)
+            );
+        }
+    }
+    Ok(())
+}
+
+/// 节点单行描述
+fn describe(node: &NodeInfo) -> String {
+    format!(
+        "{} [{}, {}] {} ({})",
+        node.tag,
+        node.protocol,
+        node.region.as_deref().unwrap_or("?"),
+        node.address,
+        &node.fingerprint[..node.fingerprint.len().min(8)]
+    )
+}
+
+fn health_label(health: Option<&NodeHealth>) -> String {
+    match health {
+        None => "untested".to_string(),
+        Some(h) if h.ok => h
+            .latency_ms
+            .map(|ms| format!("{} ms", ms))
+            .unwrap_or_else(|| "ok".to_string()),
+        Some(h) => format!("failed x{}", h.failures),
+    }
+}
+
+/// 剩余时长 (`2h05m` / `9m` / `30s`)
+fn format_secs(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m", secs / 60),
+        _ => format!("{}h{:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
@@ -0,0 +1,56 @@
+//! 采集命令 (Scrape Command)
+
+use crate::core::event::create_event_channel;
+use crate::engine::ScrapeEngine;
+use crate::interfaces::site::TaskArgs;
+use crate::ui::Ui;
+
+use super::Runtime;
+
+/// 执行单本书籍的完整采集流程
+pub async fn run(
+    rt: &Runtime,
+    site_id: String,
+    id: String,
+    params: Vec<(String, String)>,
+) -> anyhow::Result<()> {
+    // 抓取期间允许 `spider proxy` 子命令查询与控制本进程的代理
+    let _control = rt.serve_control().await;
+
+    // 建立 UI 事件反馈链路 (Event feedback loop)
+    let (event_sender, event_receiver) = create_event_channel();
+    let ui_handle = Ui::run(event_receiver);
+
+    // 任务域限制 (Scope isolation for proper RAII cleanup)
+    {
+        let mut args = TaskArgs::new();
+        args.insert("id".to_string(), id);
+        for (k, v) in params {
+            args.insert(k, v);
+        }
+
+        let ctx = rt.service_context().with_events(event_sender);
+
+        // 信号处理与优雅退出 (Signal Handling)
+        let ctx_clone = ctx.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                ctx_clone.shutdown.cancel();
+            }
+        });
+
+        let Some(site) = rt.create_site(&site_id, ctx.clone()).await else {
+            return Ok(());
+        };
+
+        let engine = ScrapeEngine::new(site, ctx.clone(), rt.config.clone());
+        let _ = engine.run(args).await;
+        ctx.persist_session();
+
+        tracing::info!("Execution flow completed for: {}", site_id);
+    }
+
+    // Await UI shutdown after event sender closure
+    let _ = ui_handle.await;
+    Ok(())
+}
@@ -0,0 +1,103 @@
+//! 检索命令 (Search Command)
+//!
+//! 调用 `Site::search` 并以表格、JSON 或纯 ID 列表输出结果。
+
+use clap::ValueEnum;
+
+use crate::core::model::SearchResult;
+use crate::interfaces::site::{Context, TaskArgs};
+
+use super::Runtime;
+
+/// 检索结果输出格式
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// 人类可读表格
+    #[default]
+    Table,
+    /// JSON 数组
+    Json,
+    /// 每行一个 ID，便于管道传递给 `scrape`
+    Ids,
+}
+
+/// 执行站内检索并输出结果
+pub async fn run(
+    rt: &Runtime,
+    site_id: String,
+    query: String,
+    page: u32,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let ctx = rt.service_context();
+    let Some(site) = rt.create_site(&site_id, ctx.clone()).await else {
+        return Ok(());
+    };
+
+    let site_ctx = Context::new(query.clone(), TaskArgs::new(), ctx.clone());
+    if let Err(e) = site.prepare(&site_ctx).await {
+        tracing::warn!("Site preparation warning: {}", e);
+    }
+
+    let results = ctx
+        .run_optimistic(format!("Search: {}", query), || {
+            let site = site.clone();
+            let site_ctx = site_ctx.clone();
+            let query = query.clone();
+            async move { site.search(&site_ctx, &query, page).await }
+        })
+        .await;
+    ctx.persist_session();
+    let results = results?;
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&results)?),
+        OutputFormat::Ids => results.iter().for_each(|r| println!("{}", r.id)),
+        OutputFormat::Table => print_table(&site_id, &results),
+    }
+
+    Ok(())
+}
+
+/// 渲染对齐表格
+fn print_table(site_id: &str, results: &[SearchResult]) {
+    if results.is_empty() {
+        println!("No results.");
+        return;
+    }
+
+    let id_width = results
+        .iter()
+        .map(|r| r.id.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(2);
+
+    println!(
+        "{:<id_width$}  {:>8}  {:<20}  {:<40}  TAGS",
+        "ID", "CHAPTERS", "AUTHOR", "TITLE"
+    );
+    for r in results {
+        let chapters = r.chapter_count.map(|c| c.to_string()).unwrap_or_default();
+        println!(
+            "{:<id_width$}  {:>8}  {:<20}  {:<40}  {}",
+            r.id,
+            chapters,
+            truncate(r.author.as_deref().unwrap_or("-"), 20),
+            truncate(&r.title, 40),
+            r.tags.join(", ")
+        );
+    }
+    println!();
+    println!("Scrape a result with: spider scrape --site {} --id <ID>", site_id);
+}
+
+/// 按字符宽度截断
+fn truncate(s: &str, max_len: usize) -> String {
+    if s.chars().count() <= max_len {
+        s.to_string()
+    } else {
+        let truncated: String = s.chars().take(max_len - 3).collect();
+        format!("{}...", truncated)
+    }
+}
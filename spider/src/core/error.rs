@@ -10,7 +10,7 @@ use reqwest::StatusCode;
 pub enum BlockReason {
     /// 触发 403 静态拦截
     IpBlocked,
-    /// 触发 Cloudflare 挑战
+    /// 触发 Cloudflare 等可由浏览器通过的反爬挑战
     Cloudflare,
     /// 触发 429 速率限制
     RateLimit,
@@ -61,6 +61,10 @@ pub enum SpiderError {
         new_url: Option<String>,
     },
 
+    /// 重定向跟随被终止 (跨域拒绝、循环或超出跳数上限)
+    #[error("Redirect aborted at {url}: {reason}")]
+    RedirectAborted { url: String, reason: String },
+
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
@@ -77,6 +81,10 @@ pub enum SpiderError {
     #[error("Soft block detected: {0}")]
     SoftBlock(BlockReason),
 
+    /// 登录失败或需要登录但未配置凭据
+    #[error("Authentication failed: {0}")]
+    AuthFailed(String),
+
     #[error("Captcha not supported or failed")]
     CaptchaFailed,
 
@@ -102,4 +110,15 @@ impl SpiderError {
             _ => None,
         }
     }
+
+    /// 提取策略层上报的重定向目标 (`RefreshRequired.new_url`)
+    pub fn redirect_target(&self) -> Option<&str> {
+        match self {
+            SpiderError::RefreshRequired { new_url, .. } => new_url.as_deref(),
+            SpiderError::Middleware(reqwest_middleware::Error::Middleware(anyhow_err)) => {
+                anyhow_err.downcast_ref::<SpiderError>().and_then(|e| e.redirect_target())
+            }
+            _ => None,
+        }
+    }
 }
@@ -37,6 +37,59 @@ impl Metadata {
     }
 }
 
+/// 检索结果条目 (Search Hit)
+///
+/// `id` 与 `scrape --id` 的输入一致，可直接用于后续采集。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub id: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cover_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chapter_count: Option<u32>,
+}
+
+/// 诊断检查项 (Diagnostic Check)
+///
+/// 由 `doctor` 命令汇总输出，任一项未通过时命令以非零状态退出。
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    /// 所属阶段 (如 metadata / chapters / content)
+    pub stage: String,
+    /// 检查项名称
+    pub name: String,
+    /// 观测结果
+    pub detail: String,
+    pub passed: bool,
+    /// 仅供参考，不计入通过与否
+    pub advisory: bool,
+}
+
+impl Check {
+    pub fn new(stage: &str, name: &str, detail: impl Into<String>, passed: bool) -> Self {
+        Self {
+            stage: stage.to_string(),
+            name: name.to_string(),
+            detail: detail.into(),
+            passed,
+            advisory: false,
+        }
+    }
+
+    /// 仅供参考、不影响结果的检查项
+    pub fn info(stage: &str, name: &str, detail: impl Into<String>) -> Self {
+        Self {
+            advisory: true,
+            ..Self::new(stage, name, detail, true)
+        }
+    }
+}
+
 /// 章节实体 (Chapter Entity)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Chapter {
@@ -4,7 +4,7 @@
 
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use tokio::sync::Semaphore;
 
@@ -12,6 +12,7 @@ use crate::core::event::{EventSender, SpiderEvent};
 use crate::interfaces::Site;
 use crate::interfaces::site::{Context, TaskArgs};
 use crate::network::context::ServiceContext;
+use crate::network::egress;
 
 /// 任务执行运行时上下文
 /// 
@@ -35,6 +36,10 @@ pub struct RuntimeContext {
     pub args: Arc<TaskArgs>,
     /// 任务唯一流水号 (Book ID)
     pub task_id: String,
+    /// 出口通道数
+    pub lanes: usize,
+    /// 原子计数：已分配出口通道的任务数
+    next_lane: AtomicUsize,
 }
 
 impl RuntimeContext {
@@ -49,6 +54,7 @@ impl RuntimeContext {
         args: Arc<TaskArgs>,
         task_id: String,
     ) -> Self {
+        let lanes = egress::count(&core.config);
         Self {
             site,
             core,
@@ -59,9 +65,16 @@ impl RuntimeContext {
             events,
             args,
             task_id,
+            lanes,
+            next_lane: AtomicUsize::new(0),
         }
     }
 
+    /// 按轮询为新任务分配出口通道
+    pub fn assign_lane(&self) -> usize {
+        self.next_lane.fetch_add(1, Ordering::Relaxed) % self.lanes
+    }
+
     /// 向事件总线推送消息
     pub fn emit(&self, event: SpiderEvent) {
         if let Some(ref sender) = self.events {
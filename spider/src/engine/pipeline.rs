@@ -16,6 +16,7 @@ use crate::core::model::{Book, BookItem, Chapter};
 use crate::interfaces::Site;
 use crate::interfaces::site::{Context, TaskArgs};
 use crate::network::context::ServiceContext;
+use crate::network::egress;
 
 use super::context::RuntimeContext;
 use super::task::{Task, TaskResult};
@@ -197,11 +198,13 @@ impl ScrapeEngine {
         {
             let task_ctx = ctx.clone();
             let task_desc = task.to_string();
+            // 多出口时任务分散到各通道，封禁仅轮换所属通道的节点
+            let lane = ctx.assign_lane();
 
-            join_set.spawn(async move {
+            join_set.spawn(egress::scope(lane, async move {
                 // 利用 run_optimistic 机制处理熔断与重试
                 task.run(task_ctx).await.map_err(|e| (task_desc, e))
-            });
+            }));
         }
     }
 
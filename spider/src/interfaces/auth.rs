@@ -0,0 +1,21 @@
+//! 站点认证协议 (Site Authentication)
+//!
+//! 定义登录动作与登录态失效判定，由通用的 `AuthPolicy` 驱动自动重新登录。
+
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use reqwest::header::HeaderMap;
+
+use crate::core::config::Credentials;
+use crate::core::error::Result;
+use crate::network::context::ServiceContext;
+
+/// 站点认证器
+#[async_trait]
+pub trait Authenticator: Send + Sync + std::fmt::Debug {
+    /// 判断响应是否表明登录态已失效 (如跳转至登录页)
+    fn is_expired(&self, status: StatusCode, headers: &HeaderMap, body: &str) -> bool;
+
+    /// 执行登录，成功后凭据已写入 `ctx.session`
+    async fn login(&self, ctx: &ServiceContext, credentials: &Credentials) -> Result<()>;
+}
@@ -0,0 +1,34 @@
+//! 验证码识别协议 (CAPTCHA Solving)
+//!
+//! 定义可串联的识别器接口，由站点策略按配置顺序依次调用。
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::core::error::Result;
+
+/// 识别结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptchaSolution {
+    /// 识别出的验证码文本
+    pub code: String,
+    /// 置信度 (0.0 ~ 1.0)
+    pub confidence: f32,
+}
+
+/// 验证码识别器
+#[async_trait]
+pub trait CaptchaSolver: Send + Sync + std::fmt::Debug {
+    /// 识别器名称 (用于配置与统计)
+    fn name(&self) -> &str;
+
+    /// 单次识别的超时上限
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(30)
+    }
+
+    /// 识别验证码图像
+    async fn solve(&self, image: &[u8]) -> Result<CaptchaSolution>;
+}
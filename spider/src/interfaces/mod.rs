@@ -1,6 +1,10 @@
+pub mod auth;
+pub mod captcha;
 pub mod policy;
 pub mod site;
 
 pub use crate::network::client::SiteClient;
+pub use auth::Authenticator;
+pub use captcha::{CaptchaSolution, CaptchaSolver};
 pub use policy::NetworkPolicy;
 pub use site::{Context, Site};
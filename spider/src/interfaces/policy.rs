@@ -3,7 +3,7 @@
 //! 定义响应校验协议及故障恢复指令。
 
 use async_trait::async_trait;
-use reqwest::Response;
+use reqwest::{Response, StatusCode};
 
 use crate::core::error::{BlockReason, Result, SpiderError};
 use crate::network::context::ServiceContext;
@@ -16,8 +16,8 @@ pub enum PolicyResult {
     /// 指令重试：拦截当前流程并请求重试。
     /// `is_force` 标记用于跳过前置阻塞检查，执行探测性质的验证请求。
     Retry { is_force: bool, reason: BlockReason },
-    /// 指令重定向：目标资源已物理迁移
-    Redirect(String),
+    /// 指令重定向：由中间件携带会话跟随至目标地址
+    Redirect { url: String, status: StatusCode },
     /// 指令终止：检测到不可恢复的逻辑异常
     Fail(SpiderError),
 }
@@ -9,8 +9,8 @@ use async_trait::async_trait;
 use url::Url;
 
 use crate::core::config::SiteConfig;
-use crate::core::error::Result;
-use crate::core::model::{BookItem, Metadata};
+use crate::core::error::{Result, SpiderError};
+use crate::core::model::{BookItem, Check, Metadata, SearchResult};
 use crate::network::client::SiteClient;
 use crate::network::context::ServiceContext;
 
@@ -47,8 +47,8 @@ pub trait Site: Send + Sync {
     /// 站点特定配置快照
     fn config(&self) -> &SiteConfig;
 
-    /// 站点根域名 URL
-    fn base_url(&self) -> &str;
+    /// 站点根域名 URL (镜像迁移后随之更新)
+    fn base_url(&self) -> String;
 
     /// 获取针对该站点优化的网络客户端
     fn client(&self) -> &SiteClient;
@@ -75,6 +75,36 @@ pub trait Site: Send + Sync {
         Ok(())
     }
 
+    /// 账号登录 (Authentication)
+    ///
+    /// 使用配置或环境变量中的凭据强制登录，登录态随会话持久化；未实现的站点返回错误。
+    async fn login(&self, _ctx: &Context) -> Result<()> {
+        Err(SpiderError::AuthFailed(format!(
+            "Login is not supported by site: {}",
+            self.id()
+        )))
+    }
+
+    /// 站内检索 (Catalog Search)
+    ///
+    /// 返回指定页码 (从 1 开始) 的结构化检索结果，未实现的站点返回错误。
+    async fn search(&self, _ctx: &Context, _query: &str, _page: u32) -> Result<Vec<SearchResult>> {
+        Err(SpiderError::Custom(format!(
+            "Search is not supported by site: {}",
+            self.id()
+        )))
+    }
+
+    /// 站点适配健康检查 (Selector Drift Check)
+    ///
+    /// 以 `ctx.args` 中的已知作品实际抓取各类页面，报告选择器命中情况；未实现的站点返回错误。
+    async fn diagnose(&self, _ctx: &Context) -> Result<Vec<Check>> {
+        Err(SpiderError::Custom(format!(
+            "Doctor is not supported by site: {}",
+            self.id()
+        )))
+    }
+
     /// 图像流水线处理 (Image Pipeline)
     ///
     /// 执行 HTML 静态分析，提取图片 URL 并将其重写为本地 EPUB 相对路径。
@@ -85,7 +115,7 @@ pub trait Site: Send + Sync {
 
         let images = RefCell::new(Vec::new());
         let mut output = Vec::new();
-        let base_url = Url::parse(self.base_url()).ok();
+        let base_url = Url::parse(&self.base_url()).ok();
 
         let mut rewriter = HtmlRewriter::new(
             Settings {
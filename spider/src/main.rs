@@ -6,6 +6,7 @@
 //! 负责 CLI 指令解析、遥测层初始化、依赖注入及系统生命周期管理。
 
 mod actors;
+mod commands;
 mod core;
 mod engine;
 mod interfaces;
@@ -15,22 +16,17 @@ mod ui;
 mod utils;
 
 use std::io;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use clap::{Parser, Subcommand};
 use tracing_subscriber::fmt::MakeWriter;
 
-use crate::core::config::AppConfig;
-use crate::core::event::create_event_channel;
-use crate::engine::ScrapeEngine;
-use crate::interfaces::Site;
-use crate::interfaces::site::TaskArgs;
-use crate::network::browser::BrowserService;
-use crate::network::context::ServiceContext;
-use crate::network::service::HttpService;
-use crate::network::session::Session;
-use crate::sites::SiteRegistry;
-use crate::ui::{Ui, get_multi};
+use crate::commands::Runtime;
+use crate::commands::cookies::CookieFormat;
+use crate::commands::search::OutputFormat;
+use crate::core::config::{AppConfig, CassetteMode};
+use crate::ui::get_multi;
 
 /// 进度条感知的日志写入器 (TUI-aware Log Writer)
 /// 
@@ -61,6 +57,12 @@ impl<'a> MakeWriter<'a> for IndicatifWriter {
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
+    /// 录制所有 HTTP 交互至指定目录
+    #[arg(long, global = true, value_name = "DIR", conflicts_with = "replay")]
+    record: Option<String>,
+    /// 仅从指定目录回放 HTTP 交互 (离线，不启用代理)
+    #[arg(long, global = true, value_name = "DIR")]
+    replay: Option<String>,
     #[command(subcommand)]
     command: Commands,
 }
@@ -79,6 +81,120 @@ enum Commands {
         #[arg(short, long, value_parser = parse_key_val)]
         params: Vec<(String, String)>,
     },
+    /// 站内检索作品，结果 ID 可直接用于 scrape
+    Search {
+        /// 目标站点标识符
+        #[arg(short, long)]
+        site: String,
+        /// 检索关键词
+        query: String,
+        /// 结果页码 (从 1 开始)
+        #[arg(short, long, default_value_t = 1)]
+        page: u32,
+        /// 输出格式
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+    /// 导入或导出站点会话凭据
+    Cookies {
+        #[command(subcommand)]
+        action: CookiesAction,
+    },
+    /// 检查运行环境；指定站点时以已知作品检查选择器是否失配 (任一项失败时非零退出)
+    Doctor {
+        /// 目标站点标识符
+        #[arg(short, long, requires = "id")]
+        site: Option<String>,
+        /// 已知可访问的作品 ID
+        #[arg(short, long, requires = "site")]
+        id: Option<String>,
+    },
+    /// 管理验证码训练样本
+    Captcha {
+        #[command(subcommand)]
+        action: CaptchaAction,
+    },
+    /// 管理代理订阅与节点
+    Proxy {
+        #[command(subcommand)]
+        action: ProxyAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProxyAction {
+    /// 忽略缓存立即重新拉取订阅
+    Refresh,
+    /// 列出节点及其协议、健康状况与隔离状态
+    List,
+    /// 立即探测节点连通性与延迟
+    Test {
+        /// 节点标签 (可为片段) 或序号，缺省为全部节点
+        node: Option<String>,
+    },
+    /// 固定使用指定节点，固定期间不再自动轮换
+    Use {
+        /// 节点标签 (可为片段) 或序号
+        #[arg(required_unless_present = "auto")]
+        node: Option<String>,
+        /// 解除固定，恢复自动轮换
+        #[arg(long, conflicts_with = "node")]
+        auto: bool,
+    },
+    /// 显示当前出口节点与运行状态
+    Status,
+    /// 暂停正在运行的抓取的自动轮换，各通道保持当前节点
+    Pause,
+    /// 恢复正在运行的抓取的自动轮换
+    Resume,
+}
+
+#[derive(Subcommand)]
+enum CaptchaAction {
+    /// 导出已记录的验证码图像与标签
+    Export {
+        /// 目标站点标识符
+        #[arg(short, long)]
+        site: String,
+        /// 输出目录
+        out: PathBuf,
+        /// 仅导出本地 OCR 未识别正确的样本
+        #[arg(long)]
+        failures: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CookiesAction {
+    /// 导入 Netscape cookies.txt 或 JSON 凭据文件
+    Import {
+        /// 目标站点标识符
+        #[arg(short, long)]
+        site: String,
+        /// 凭据文件路径
+        file: PathBuf,
+        /// 获取凭据时浏览器使用的 User-Agent
+        #[arg(long)]
+        ua: Option<String>,
+        /// 目标代理节点指纹 (`direct` 表示直连，缺省为最近一次抓取使用的节点)
+        #[arg(long)]
+        node: Option<String>,
+    },
+    /// 导出站点当前凭据
+    Export {
+        /// 目标站点标识符
+        #[arg(short, long)]
+        site: String,
+        /// 输出文件路径 (`-` 表示标准输出)
+        #[arg(default_value = "-")]
+        file: PathBuf,
+        /// 输出格式
+        #[arg(short, long, value_enum, default_value_t = CookieFormat::Netscape)]
+        format: CookieFormat,
+        /// 源代理节点指纹 (`direct` 表示直连，缺省为最近一次抓取使用的节点)
+        #[arg(long)]
+        node: Option<String>,
+    },
 }
 
 #[tokio::main]
@@ -98,65 +214,85 @@ async fn main() -> anyhow::Result<()> {
         .init();
 
     // 依赖项初始化与注入 (Dependency Injection)
-    let config = Arc::new(AppConfig::load()?);
     let cli = Cli::parse();
 
-    let (proxy_tx, _proxy_handle) = actors::proxy::ProxyManager::start(config.clone()).await;
-    let session = Arc::new(Session::new());
-    let http = Arc::new(HttpService::new(config.clone(), session.clone()));
-    let browser = Arc::new(BrowserService::new(config.clone()));
-    let registry = SiteRegistry::new();
+    // 环境检查自行加载配置，以便报告配置错误
+    if let Commands::Doctor { site: None, .. } = cli.command {
+        return commands::doctor::env().await;
+    }
+
+    let mut config = AppConfig::load()?;
 
-    match cli.command {
+    if let Some(dir) = cli.record {
+        config.set_cassette(CassetteMode::Record, Some(dir));
+    } else if let Some(dir) = cli.replay {
+        config.set_cassette(CassetteMode::Replay, Some(dir));
+    }
+
+    // 凭据与代理命令不启动代理 Actor，以免启动时的节点轮换改写代理状态
+    let command = match cli.command {
+        Commands::Cookies { action } => {
+            match action {
+                CookiesAction::Import {
+                    site: site_id,
+                    file,
+                    ua,
+                    node,
+                } => commands::cookies::import(&config, site_id, file, ua, node)?,
+                CookiesAction::Export {
+                    site: site_id,
+                    file,
+                    format,
+                    node,
+                } => commands::cookies::export(&config, site_id, file, format, node)?,
+            }
+            return Ok(());
+        }
+        Commands::Proxy { action } => {
+            let config = Arc::new(config);
+            match action {
+                ProxyAction::Refresh => commands::proxy::refresh(&config).await?,
+                ProxyAction::List => commands::proxy::list(&config).await?,
+                ProxyAction::Test { node } => commands::proxy::test(&config, node).await?,
+                ProxyAction::Use { node, .. } => commands::proxy::use_node(&config, node).await?,
+                ProxyAction::Status => commands::proxy::status(&config).await?,
+                ProxyAction::Pause => commands::proxy::pause(&config).await?,
+                ProxyAction::Resume => commands::proxy::resume(&config).await?,
+            }
+            return Ok(());
+        }
+        command => command,
+    };
+
+    let rt = Runtime::bootstrap(Arc::new(config)).await;
+
+    match command {
         Commands::Scrape {
             site: site_id,
             id,
             params,
-        } => {
-            // 建立 UI 事件反馈链路 (Event feedback loop)
-            let (event_sender, event_receiver) = create_event_channel();
-            let ui_handle = Ui::run(event_receiver);
-
-            // 任务域限制 (Scope isolation for proper RAII cleanup)
-            {
-                let mut args = TaskArgs::new();
-                args.insert("id".to_string(), id);
-                for (k, v) in params {
-                    args.insert(k, v);
-                }
-
-                let session = Arc::new(Session::new());
-                session.set_ua("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36".into());
-
-                let ctx = ServiceContext::new(http, session, proxy_tx, browser, config.clone())
-                    .with_events(event_sender);
-
-                // 信号处理与优雅退出 (Signal Handling)
-                let ctx_clone = ctx.clone();
-                tokio::spawn(async move {
-                    if tokio::signal::ctrl_c().await.is_ok() {
-                        ctx_clone.shutdown.cancel();
-                    }
-                });
-
-                let site_cfg = config.sites.get(&site_id).cloned().unwrap_or_default();
-                let site: Arc<dyn Site> = match registry.create(&site_id, site_cfg, ctx.clone()) {
-                    Some(s) => Arc::from(s),
-                    None => {
-                        tracing::error!("Unknown site identifier: {}", site_id);
-                        return Ok(());
-                    }
-                };
-
-                let engine = ScrapeEngine::new(site, ctx, config.clone());
-                let _ = engine.run(args).await;
-
-                tracing::info!("Execution flow completed for: {}", site_id);
+        } => commands::scrape::run(&rt, site_id, id, params).await?,
+        Commands::Search {
+            site: site_id,
+            query,
+            page,
+            format,
+        } => commands::search::run(&rt, site_id, query, page, format).await?,
+        Commands::Cookies { .. } | Commands::Proxy { .. } => {
+            unreachable!("handled before bootstrap")
+        }
+        Commands::Doctor { site, id } => {
+            if let (Some(site_id), Some(id)) = (site, id) {
+                commands::doctor::site(&rt, site_id, id).await?
             }
-
-            // Await UI shutdown after event sender closure
-            let _ = ui_handle.await;
         }
+        Commands::Captcha { action } => match action {
+            CaptchaAction::Export {
+                site: site_id,
+                out,
+                failures,
+            } => commands::captcha::export(&rt, site_id, out, failures).await?,
+        },
     }
 
     Ok(())
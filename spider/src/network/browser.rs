@@ -2,30 +2,38 @@
 //!
 //! 封装基于 CDP 的浏览器操作，主要用于解决 Cloudflare 挑战及指纹特征提取。
 
-use std::path::Path;
-use std::sync::OnceLock;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
 use chromiumoxide::{
     Page,
     browser::{Browser, BrowserConfig},
+    cdp::browser_protocol::network::{CookieParam, TimeSinceEpoch},
     cdp::browser_protocol::page::AddScriptToEvaluateOnNewDocumentParams,
+    detection::{DetectionOptions, default_executable},
 };
 use futures::StreamExt;
+use reqwest::StatusCode;
 use reqwest::header::{HeaderMap, HeaderValue};
 use tokio::{
-    sync::OnceCell,
+    sync::{Mutex, OnceCell, OwnedSemaphorePermit, Semaphore},
     task::JoinHandle,
     time::{interval, timeout},
 };
 use tracing::{debug, info};
+use url::Url;
 
-use crate::core::config::AppConfig;
+use crate::core::config::{AppConfig, ChallengeReason};
 use crate::core::error::{BlockReason, Result, SpiderError};
+use crate::network::client::Page as RenderedPage;
+use crate::network::cookie::CookieSpec;
+use crate::network::policies::ChallengeDetector;
 
 /// 隐身补丁脚本
 static STEALTH_JS: &str = include_str!("../../stealth.min.js");
-/// 指纹缓存
+/// 指纹缓存 (首次启动浏览器时提取)
 static UA_CACHE: OnceCell<String> = OnceCell::const_new();
 
 /// 浏览器端标准请求头集
@@ -44,46 +52,251 @@ fn get_default_headers() -> &'static HeaderMap {
     })
 }
 
-/// 浏览器会话容器 (Browser Session)
-/// 
-/// 负责管理 Chromium 进程的生命周期及 CDP 事件循环。
-pub struct BrowserSession {
-    /// 浏览器实例
-    browser: Option<Browser>,
+/// 浏览器实例 (Browser Instance)
+///
+/// 持有 Chromium 进程及其 CDP 事件循环；事件循环退出即视为进程崩溃。
+struct BrowserInstance {
+    /// 浏览器进程
+    browser: Browser,
     /// 事件循环句柄
-    handler: Option<JoinHandle<()>>,
+    handler: JoinHandle<()>,
+    /// 事件循环存活标记
+    alive: Arc<AtomicBool>,
+    /// 实例代数，用于识别跨重启的过期页面
+    generation: u64,
 }
 
-impl BrowserSession {
-    /// 启动浏览器实例并初始化事件循环
-    pub async fn launch(config: &AppConfig) -> Result<Self> {
-        let ua = UA_CACHE.get_or_init(Self::probe_native_ua).await;
-        let browser_config = build_browser_config(config, ua)?;
+impl BrowserInstance {
+    /// 启动浏览器进程并初始化事件循环
+    async fn launch(config: &AppConfig, generation: u64) -> Result<Self> {
+        let browser_config = build_browser_config(config)?;
 
         let (browser, mut handler) = Browser::launch(browser_config)
             .await
             .map_err(|e| SpiderError::Browser(e.to_string()))?;
 
+        let alive = Arc::new(AtomicBool::new(true));
+        let flag = alive.clone();
         let handle = tokio::spawn(async move {
             while let Some(h) = handler.next().await {
                 if h.is_err() {
                     break;
                 }
             }
+            flag.store(false, Ordering::Release);
         });
 
         Ok(Self {
-            browser: Some(browser),
-            handler: Some(handle),
+            browser,
+            handler: handle,
+            alive,
+            generation,
         })
     }
 
-    /// 创建预置 Stealth 脚本的新页面
-    pub async fn new_page(&self) -> Result<Page> {
-        let browser = self
-            .browser
-            .as_ref()
-            .ok_or_else(|| SpiderError::Browser("Browser session already closed".into()))?;
+    fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::Acquire) && !self.handler.is_finished()
+    }
+
+    /// 原生 User-Agent 提取 (去除 Headless 特征)
+    async fn native_ua(&self) -> String {
+        match self.browser.user_agent().await {
+            Ok(ua) => {
+                let clean_ua = ua
+                    .replace("HeadlessChrome", "Chrome")
+                    .replace("Headless", "");
+                debug!("Native UA probed: {}", clean_ua);
+                clean_ua
+            }
+            Err(e) => {
+                debug!("Native UA probing failed: {}", e);
+                fallback_ua()
+            }
+        }
+    }
+
+    /// 优雅关闭浏览器进程并回收事件循环
+    async fn shutdown(mut self) {
+        if self.is_alive() {
+            let _ = self.browser.close().await;
+        }
+        let _ = self.browser.kill().await;
+        let _ = self.handler.await;
+    }
+}
+
+fn fallback_ua() -> String {
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36".to_string()
+}
+
+/// 浏览器池内部状态
+#[derive(Default)]
+struct PoolState {
+    /// 当前浏览器实例 (惰性启动)
+    instance: Option<BrowserInstance>,
+    /// 已归还的空闲页面
+    idle: Vec<Page>,
+    /// 累计启动次数
+    launches: u64,
+    /// 最近一次使用时间
+    last_used: Option<Instant>,
+}
+
+/// 浏览器池 (Browser Pool)
+///
+/// 复用单个长驻 Chromium 进程及其标签页：首次使用时惰性启动，进程崩溃后自动重启，
+/// 空闲超过 `idle_timeout_secs` 后由后台任务回收。并发页面数受 `max_pages` 约束。
+pub struct BrowserPool {
+    config: Arc<AppConfig>,
+    state: Mutex<PoolState>,
+    /// 并发页面许可
+    permits: Arc<Semaphore>,
+    /// 空闲回收任务是否已启动
+    reaper_started: AtomicBool,
+}
+
+/// 池化页面 (Pooled Page)
+///
+/// 持有并发许可；使用完毕应交还 `BrowserPool::release`，异常页面交由 `discard` 关闭。
+pub struct PooledPage {
+    page: Option<Page>,
+    generation: u64,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl std::ops::Deref for PooledPage {
+    type Target = Page;
+
+    fn deref(&self) -> &Page {
+        self.page.as_ref().expect("pooled page already returned")
+    }
+}
+
+impl Drop for PooledPage {
+    fn drop(&mut self) {
+        // 未归还的页面状态未知，直接关闭
+        if let Some(page) = self.page.take() {
+            tokio::spawn(async move {
+                let _ = page.close().await;
+            });
+        }
+    }
+}
+
+impl BrowserPool {
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        let max_pages = config.browser.max_pages.max(1);
+        Self {
+            config,
+            state: Mutex::new(PoolState::default()),
+            permits: Arc::new(Semaphore::new(max_pages)),
+            reaper_started: AtomicBool::new(false),
+        }
+    }
+
+    /// 获取一个可用页面
+    ///
+    /// 优先复用空闲页面；浏览器未启动或已崩溃时重新启动。
+    pub async fn acquire(self: &Arc<Self>) -> Result<PooledPage> {
+        let permit = self
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|e| SpiderError::Browser(e.to_string()))?;
+        self.spawn_reaper();
+
+        let mut guard = self.state.lock().await;
+        let state = &mut *guard;
+        state.last_used = Some(Instant::now());
+
+        if let Some(instance) = &state.instance
+            && !instance.is_alive()
+        {
+            info!("Browser process exited unexpectedly, relaunching...");
+            Self::reset(state).await;
+        }
+
+        if state.instance.is_none() {
+            state.launches += 1;
+            let instance = BrowserInstance::launch(&self.config, state.launches).await?;
+            let ua = UA_CACHE.get_or_init(|| instance.native_ua()).await;
+            debug!(
+                "Browser launched (generation {}, UA: {})",
+                state.launches, ua
+            );
+            state.instance = Some(instance);
+        }
+
+        let instance = state.instance.as_ref().expect("browser instance launched");
+        let generation = instance.generation;
+
+        let page = match state.idle.pop() {
+            Some(page) => Ok(page),
+            None => Self::new_page(&instance.browser).await,
+        };
+        let page = match page {
+            Ok(page) => page,
+            Err(e) => {
+                // 创建页面失败通常意味着连接已断开，下次获取时重启
+                Self::reset(state).await;
+                return Err(e);
+            }
+        };
+
+        Ok(PooledPage {
+            page: Some(page),
+            generation,
+            _permit: permit,
+        })
+    }
+
+    /// 归还页面供后续复用
+    pub async fn release(&self, mut pooled: PooledPage) {
+        let Some(page) = pooled.page.take() else {
+            return;
+        };
+
+        // 导航在锁外完成，卡住的页面不会阻塞其他页面的获取与归还
+        let blank = matches!(
+            timeout(RELEASE_TIMEOUT, page.goto("about:blank")).await,
+            Ok(Ok(_))
+        );
+
+        let page = {
+            let mut state = self.state.lock().await;
+            state.last_used = Some(Instant::now());
+
+            let current = state.instance.as_ref().map(|i| i.generation);
+            let reusable = blank
+                && current == Some(pooled.generation)
+                && state.idle.len() < self.config.browser.max_pages;
+            if reusable {
+                state.idle.push(page);
+                return;
+            }
+            page
+        };
+        let _ = page.close().await;
+    }
+
+    /// 关闭异常页面，不再复用
+    pub async fn discard(&self, mut pooled: PooledPage) {
+        if let Some(page) = pooled.page.take() {
+            let _ = page.close().await;
+        }
+    }
+
+    /// 关闭浏览器进程 (下次获取时重新启动)
+    ///
+    /// 切换代理节点后调用，避免浏览器沿用旧出口的长连接。
+    pub async fn shutdown(&self) {
+        let mut state = self.state.lock().await;
+        Self::reset(&mut state).await;
+    }
+
+    /// 创建预置 Stealth 脚本与原生 UA 的新页面
+    async fn new_page(browser: &Browser) -> Result<Page> {
         let page = browser
             .new_page("about:blank")
             .await
@@ -98,91 +311,80 @@ impl BrowserSession {
             debug!("Stealth script injection failed: {}", e);
         }
 
+        if let Some(ua) = UA_CACHE.get()
+            && let Err(e) = page.set_user_agent(ua.as_str()).await
+        {
+            debug!("User-Agent override failed: {}", e);
+        }
+
         Ok(page)
     }
 
-    /// 优雅关闭浏览器会话并回收资源
-    pub async fn close(&mut self) -> Result<()> {
-        let browser = self.browser.take();
-        let handler = self.handler.take();
-
-        if let Some(mut b) = browser {
-            let _ = b.close().await;
-            if let Some(h) = handler {
-                let _ = h.await;
-            }
+    async fn reset(state: &mut PoolState) {
+        state.idle.clear();
+        if let Some(instance) = state.instance.take() {
+            instance.shutdown().await;
         }
-        Ok(())
     }
 
-    /// 原生 User-Agent 探测 (Fingerprint Probing)
-    async fn probe_native_ua() -> String {
-        debug!("Probing native system User-Agent...");
-
-        let config = match BrowserConfig::builder()
-            .arg("--headless=new")
-            .arg("--no-sandbox")
-            .build()
-        {
-            Ok(c) => c,
-            Err(_) => return Self::fallback_ua(),
-        };
+    /// 启动空闲回收任务 (仅一次)
+    ///
+    /// 任务持有弱引用，池被释放后自动退出。
+    fn spawn_reaper(self: &Arc<Self>) {
+        if self.reaper_started.swap(true, Ordering::AcqRel) {
+            return;
+        }
 
-        let (mut browser, mut handler) = match Browser::launch(config).await {
-            Ok(b) => b,
-            Err(_) => return Self::fallback_ua(),
-        };
+        let idle_timeout = Duration::from_secs(self.config.browser.idle_timeout_secs);
+        if idle_timeout.is_zero() {
+            return;
+        }
 
-        let handle = tokio::spawn(async move {
-            while let Some(h) = handler.next().await {
-                if h.is_err() {
+        let pool = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = interval((idle_timeout / 4).max(Duration::from_secs(1)));
+            loop {
+                ticker.tick().await;
+                let Some(pool) = pool.upgrade() else {
                     break;
-                }
+                };
+                pool.reap_idle(idle_timeout).await;
             }
         });
+    }
 
-        let result = async {
-            let page = browser.new_page("about:blank").await?;
-            let ua: String = page.evaluate("navigator.userAgent").await?.into_value()?;
-            Ok::<String, chromiumoxide::error::CdpError>(ua)
+    /// 回收空闲超时且无页面占用的浏览器
+    async fn reap_idle(&self, idle_timeout: Duration) {
+        let max_pages = self.config.browser.max_pages.max(1);
+        if self.permits.available_permits() < max_pages {
+            return;
         }
-        .await;
-
-        let _ = browser.close().await;
-        let _ = handle.await;
-        tokio::time::sleep(Duration::from_millis(100)).await;
-        drop(browser);
 
-        match result {
-            Ok(ua) => {
-                let clean_ua = ua
-                    .replace("HeadlessChrome", "Chrome")
-                    .replace("Headless", "");
-                debug!("Native UA probed: {}", clean_ua);
-                clean_ua
-            }
-            Err(_) => Self::fallback_ua(),
+        let mut state = self.state.lock().await;
+        let expired = state.last_used.is_some_and(|t| t.elapsed() >= idle_timeout);
+        if expired && state.instance.is_some() {
+            info!("Browser idle for {:?}, shutting down", idle_timeout);
+            Self::reset(&mut state).await;
         }
     }
-
-    fn fallback_ua() -> String {
-        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36".to_string()
-    }
 }
 
 /// 构建 Chromium 启动参数集
-fn build_browser_config(config: &AppConfig, ua: &str) -> Result<BrowserConfig> {
+fn build_browser_config(config: &AppConfig) -> Result<BrowserConfig> {
     let mut builder = BrowserConfig::builder()
         .arg("--disable-blink-features=AutomationControlled")
-        .arg(format!("--user-agent={}", ua))
         .arg("--disable-infobars")
         .arg("--no-sandbox")
         .arg("--window-size=1920,1080")
-        .arg("--disable-extensions")
-        .arg(format!(
+        .arg("--disable-extensions");
+
+    // 与 HTTP 客户端共用本地代理出口
+    if config.proxy.enabled {
+        builder = builder.arg(format!(
             "--proxy-server=http://127.0.0.1:{}",
             config.proxy.proxy_port
         ));
+    }
 
     if config.browser.headless {
         builder = builder.arg("--headless=new");
@@ -190,50 +392,130 @@ fn build_browser_config(config: &AppConfig, ua: &str) -> Result<BrowserConfig> {
         builder = builder.with_head();
     }
 
-    let chrome_path = if let Some(path) = &config.browser.chrome_path {
-        Some(path.clone())
-    } else {
-        [
-            r"C:\Program Files\Google\Chrome\Application\chrome.exe",
-            r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe",
-            r"C:\Program Files (x86)\Microsoft\Edge\Application\msedge.exe",
-            r"C:\Program Files\Microsoft\Edge\Application\msedge.exe",
-        ]
-        .iter()
-        .find(|p| Path::new(p).exists())
-        .map(|p| p.to_string())
-    };
-
-    if let Some(path) = chrome_path {
-        builder = builder.chrome_executable(path);
+    match find_chrome_executable(config) {
+        Some(path) => {
+            debug!("Using browser executable: {}", path.display());
+            builder = builder.chrome_executable(path);
+        }
+        None => {
+            return Err(SpiderError::Browser(
+                "No Chrome/Chromium executable found, set browser.chrome_path or CHROME_PATH"
+                    .into(),
+            ));
+        }
     }
 
     builder.build().map_err(SpiderError::Browser)
 }
 
-impl Drop for BrowserSession {
-    fn drop(&mut self) {
-        if self.browser.is_some() {
-            let mut browser = self.browser.take().unwrap();
-            let handler = self.handler.take();
-            tokio::spawn(async move {
-                let _ = browser.close().await;
-                if let Some(h) = handler {
-                    let _ = h.await;
-                }
-            });
+/// 可执行文件名候选 (PATH 查找)
+const CHROME_BINARIES: &[&str] = &[
+    "google-chrome",
+    "google-chrome-stable",
+    "chromium",
+    "chromium-browser",
+    "chrome",
+    "microsoft-edge",
+    "microsoft-edge-stable",
+];
+
+/// 常见安装路径候选
+#[cfg(target_os = "windows")]
+const CHROME_INSTALL_PATHS: &[&str] = &[
+    r"C:\Program Files\Google\Chrome\Application\chrome.exe",
+    r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe",
+    r"C:\Program Files (x86)\Microsoft\Edge\Application\msedge.exe",
+    r"C:\Program Files\Microsoft\Edge\Application\msedge.exe",
+];
+#[cfg(target_os = "macos")]
+const CHROME_INSTALL_PATHS: &[&str] = &[
+    "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+    "/Applications/Chromium.app/Contents/MacOS/Chromium",
+    "/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const CHROME_INSTALL_PATHS: &[&str] = &[
+    "/usr/bin/google-chrome",
+    "/usr/bin/google-chrome-stable",
+    "/usr/bin/chromium",
+    "/usr/bin/chromium-browser",
+    "/snap/bin/chromium",
+    "/opt/google/chrome/chrome",
+    "/usr/lib/chromium/chromium",
+];
+
+/// 浏览器可执行文件发现 (Executable Discovery)
+///
+/// 查找顺序：配置项 `chrome_path` → 环境变量 `CHROME_PATH` → PATH 查找 → 平台常见安装路径
+/// → chromiumoxide 内置探测 (含 Windows 注册表)。
+pub fn find_chrome_executable(config: &AppConfig) -> Option<PathBuf> {
+    if let Some(path) = &config.browser.chrome_path {
+        return Some(PathBuf::from(path));
+    }
+
+    if let Some(path) = std::env::var_os("CHROME_PATH").filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+
+    if let Some(path) = std::env::var_os("PATH") {
+        let found = std::env::split_paths(&path).find_map(|dir| {
+            CHROME_BINARIES.iter().find_map(|name| {
+                let candidate = dir
+                    .join(name)
+                    .with_extension(std::env::consts::EXE_EXTENSION);
+                candidate.is_file().then_some(candidate)
+            })
+        });
+        if found.is_some() {
+            return found;
         }
     }
+
+    let mut candidates: Vec<PathBuf> = CHROME_INSTALL_PATHS.iter().map(PathBuf::from).collect();
+    // macOS 用户级安装目录 (~/Applications)
+    if cfg!(target_os = "macos")
+        && let Some(home) = std::env::var_os("HOME")
+    {
+        let home = Path::new(&home);
+        candidates.extend(
+            CHROME_INSTALL_PATHS
+                .iter()
+                .filter_map(|p| p.strip_prefix('/'))
+                .map(|p| home.join(p)),
+        );
+    }
+
+    candidates
+        .into_iter()
+        .find(|p| p.is_file())
+        .or_else(|| default_executable(DetectionOptions::default()).ok())
 }
 
+/// 挑战通过后下发的凭据 Cookie 名称前缀 (Cloudflare / DDoS-Guard / Sucuri)
+const CLEARANCE_COOKIES: &[&str] = &["cf_clearance", "__ddg2", "sucuri_cloudproxy_uuid_"];
+
 /// 自动化挑战解决服务 (Challenge Resolution Service)
 pub struct BrowserService {
-    config: std::sync::Arc<AppConfig>,
+    config: Arc<AppConfig>,
+    /// 长驻浏览器池
+    pool: Arc<BrowserPool>,
+    /// 挑战页识别 (内置规则)
+    detector: ChallengeDetector,
 }
 
 impl BrowserService {
-    pub fn new(config: std::sync::Arc<AppConfig>) -> Self {
-        Self { config }
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        let pool = Arc::new(BrowserPool::new(config.clone()));
+        Self {
+            config,
+            pool,
+            detector: ChallengeDetector::default(),
+        }
+    }
+
+    /// 浏览器池句柄
+    pub fn pool(&self) -> &Arc<BrowserPool> {
+        &self.pool
     }
 
     /// 执行挑战绕过流程
@@ -258,7 +540,10 @@ impl BrowserService {
                 Err(e) => {
                     info!("Verification attempt failed, rotating proxy: {}", e);
                     last_error = Some(e);
-                    ctx.force_rotate_proxy().await;
+                    // 丢弃沿用旧出口的浏览器连接
+                    self.pool.shutdown().await;
+                    // 浏览器固定经主通道出口
+                    ctx.force_rotate_lane(0, BlockReason::Cloudflare).await;
                     tokio::time::sleep(Duration::from_secs(2)).await;
                 }
             }
@@ -275,11 +560,12 @@ impl BrowserService {
         url: &str,
         ctx: &crate::network::context::ServiceContext,
     ) -> Result<()> {
-        let mut session = BrowserSession::launch(&self.config).await?;
-        let result = self.execute_page_logic(&session, url, ctx).await;
+        let page = self.pool.acquire().await?;
+        let result = self.execute_page_logic(&page, url, ctx).await;
 
-        if let Err(e) = session.close().await {
-            debug!("Non-fatal error during browser closure: {}", e);
+        match result {
+            Ok(_) => self.pool.release(page).await,
+            Err(_) => self.pool.discard(page).await,
         }
 
         result
@@ -288,18 +574,16 @@ impl BrowserService {
     /// 页面交互逻辑
     async fn execute_page_logic(
         &self,
-        session: &BrowserSession,
+        page: &Page,
         url: &str,
         ctx: &crate::network::context::ServiceContext,
     ) -> Result<()> {
-        let page = session.new_page().await?;
-
         page.goto(url)
             .await
             .map_err(|e| SpiderError::Browser(e.to_string()))?;
 
-        self.wait_for_challenge(&page).await?;
-        self.extract_and_save_data(&page, ctx).await?;
+        self.wait_for_challenge(page).await?;
+        self.extract_and_save_data(page, ctx).await?;
 
         Ok(())
     }
@@ -324,23 +608,33 @@ impl BrowserService {
                     return Err(SpiderError::SoftBlock(BlockReason::IpBlocked));
                 }
 
-                // 挑战页检测
-                if title.is_empty()
-                    || title.contains("just a moment")
-                    || title.contains("cloudflare")
-                {
-                    debug!("Waiting for Cloudflare redirect (Title: {})...", title);
+                // 挑战页检测 (复用响应侧的正文标记规则)
+                if title.is_empty() {
+                    continue;
+                }
+                let html = page.content().await.unwrap_or_default();
+                if let Some(rule) = self.detector.detect(200, &HeaderMap::new(), &html) {
+                    if rule.reason != ChallengeReason::Challenge {
+                        return Err(SpiderError::SoftBlock(rule.reason.into()));
+                    }
+                    debug!(
+                        "Waiting for challenge redirect ({}, Title: {})...",
+                        rule.name, title
+                    );
                     continue;
                 }
 
                 // 持久凭据校验 (Credential Validation)
                 let cookies = page.get_cookies().await.unwrap_or_default();
-                if cookies.iter().any(|c| c.name == "cf_clearance") {
-                    debug!("Challenge solved, cf_clearance acquired (Title: {})", title);
+                if let Some(c) = cookies
+                    .iter()
+                    .find(|c| CLEARANCE_COOKIES.iter().any(|p| c.name.starts_with(p)))
+                {
+                    debug!("Challenge solved, {} acquired (Title: {})", c.name, title);
                     return Ok(());
                 }
 
-                debug!("Page transitioned but cf_clearance missing, still waiting...");
+                debug!("Page transitioned but clearance cookie missing, still waiting...");
             }
         })
         .await
@@ -354,32 +648,206 @@ impl BrowserService {
         ctx: &crate::network::context::ServiceContext,
     ) -> Result<()> {
         // 同步 Cookie 凭据
-        let cookies = page
-            .get_cookies()
-            .await
-            .map_err(|e| SpiderError::Browser(e.to_string()))?;
-
-        if !cookies.is_empty() {
-            let cookie_str = cookies
-                .iter()
-                .map(|c| format!("{}={}", c.name, c.value))
-                .collect::<Vec<_>>()
-                .join("; ");
-            ctx.session.set_cookie(cookie_str);
-        }
+        import_page_cookies(page, ctx).await?;
 
         // 同步 User-Agent
         if let Ok(ua_val) = page.evaluate("navigator.userAgent").await {
             if let Ok(ua) = ua_val.into_value::<String>() {
                 if !ua.is_empty() {
-                    ctx.session.set_ua(ua);
+                    ctx.lane_session(0).set_ua(ua);
                 }
             }
         }
 
         // 注入默认浏览器上下文请求头
-        ctx.session.set_headers(get_default_headers().clone());
+        ctx.lane_session(0)
+            .set_headers(get_default_headers().clone());
+
+        // 挑战凭据可跨运行复用
+        ctx.persist_lane_session(0);
 
         Ok(())
     }
 }
+
+/// 渲染完成判定条件 (Wait Condition)
+#[derive(Debug, Clone)]
+pub enum WaitCondition {
+    /// 指定 CSS 选择器的元素出现
+    Selector(String),
+    /// 文档加载完成且资源请求数稳定 (网络空闲)
+    NetworkIdle,
+    /// 固定等待时长
+    Timeout(Duration),
+}
+
+/// 渲染等待上限
+const RENDER_TIMEOUT: Duration = Duration::from_secs(30);
+/// 归还页面时重置导航的等待上限
+const RELEASE_TIMEOUT: Duration = Duration::from_secs(5);
+/// 网络空闲判定窗口
+const NETWORK_IDLE_WINDOW: Duration = Duration::from_millis(500);
+/// 已加载资源计数脚本 (网络空闲探测)
+const RESOURCE_PROBE_JS: &str =
+    "document.readyState === 'complete' ? performance.getEntriesByType('resource').length : -1";
+
+impl WaitCondition {
+    /// 在页面上等待条件满足
+    async fn wait(&self, page: &Page) -> Result<()> {
+        match self {
+            Self::Timeout(duration) => {
+                tokio::time::sleep(*duration).await;
+                Ok(())
+            }
+            Self::Selector(selector) => timeout(RENDER_TIMEOUT, async {
+                let mut ticker = interval(Duration::from_millis(200));
+                loop {
+                    ticker.tick().await;
+                    if page.find_element(selector.as_str()).await.is_ok() {
+                        return;
+                    }
+                }
+            })
+            .await
+            .map_err(|_| {
+                SpiderError::Browser(format!("Timed out waiting for selector: {}", selector))
+            }),
+            Self::NetworkIdle => timeout(RENDER_TIMEOUT, async {
+                let mut ticker = interval(NETWORK_IDLE_WINDOW);
+                let mut last = -1i64;
+                loop {
+                    ticker.tick().await;
+                    let count = match page.evaluate(RESOURCE_PROBE_JS).await {
+                        Ok(v) => v.into_value::<i64>().unwrap_or(-1),
+                        Err(_) => -1,
+                    };
+                    // 连续两个窗口内无新增资源请求视为空闲
+                    if count >= 0 && count == last {
+                        return;
+                    }
+                    last = count;
+                }
+            })
+            .await
+            .map_err(|_| SpiderError::Browser("Timed out waiting for network idle".into())),
+        }
+    }
+}
+
+impl BrowserService {
+    /// 浏览器渲染抓取 (Rendered Fetch)
+    ///
+    /// 以会话的 Cookie 与 UA 打开页面，等待条件满足后返回渲染后的 DOM；
+    /// 浏览器下发的 Cookie 回写至会话。
+    pub async fn fetch_rendered(
+        &self,
+        url: &str,
+        wait: &WaitCondition,
+        ctx: &crate::network::context::ServiceContext,
+    ) -> Result<RenderedPage> {
+        let page = self.pool.acquire().await?;
+        let result = self.render_page(&page, url, wait, ctx).await;
+
+        match result {
+            Ok(_) => self.pool.release(page).await,
+            Err(_) => self.pool.discard(page).await,
+        }
+
+        result
+    }
+
+    async fn render_page(
+        &self,
+        page: &Page,
+        url: &str,
+        wait: &WaitCondition,
+        ctx: &crate::network::context::ServiceContext,
+    ) -> Result<RenderedPage> {
+        let browser_err = |e: chromiumoxide::error::CdpError| SpiderError::Browser(e.to_string());
+
+        // 会话身份同步至浏览器 (浏览器固定经主通道出口，使用主通道会话)
+        page.set_user_agent(ctx.lane_session(0).get_ua())
+            .await
+            .map_err(browser_err)?;
+        let cookies: Vec<CookieParam> = ctx
+            .lane_session(0)
+            .cookies()
+            .iter()
+            .filter_map(CookieSpec::from_stored)
+            .map(cookie_param)
+            .collect();
+        if !cookies.is_empty() {
+            page.set_cookies(cookies).await.map_err(browser_err)?;
+        }
+
+        debug!("Rendering {}", url);
+        page.goto(url).await.map_err(browser_err)?;
+        wait.wait(page).await?;
+
+        let text = page.content().await.map_err(browser_err)?;
+        let final_url = page
+            .url()
+            .await
+            .map_err(browser_err)?
+            .unwrap_or_else(|| url.to_string());
+        let final_url = Url::parse(&final_url)
+            .map_err(|e| SpiderError::Custom(format!("Invalid URL: {}", e)))?;
+
+        import_page_cookies(page, ctx).await?;
+        ctx.persist_lane_session(0);
+
+        // 渲染页面无法取得原始响应，状态码按成功记录
+        Ok(RenderedPage {
+            url: final_url,
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            text,
+        })
+    }
+}
+
+/// 浏览器 Cookie 回写至主通道会话
+async fn import_page_cookies(
+    page: &Page,
+    ctx: &crate::network::context::ServiceContext,
+) -> Result<()> {
+    let cookies = page
+        .get_cookies()
+        .await
+        .map_err(|e| SpiderError::Browser(e.to_string()))?;
+
+    let imported = cookies
+        .into_iter()
+        .filter(|c| {
+            ctx.lane_session(0).import_cookie(CookieSpec {
+                expires: (!c.session && c.expires > 0.0).then_some(c.expires as i64),
+                name: c.name.clone(),
+                value: c.value.clone(),
+                domain: c.domain.clone(),
+                path: c.path.clone(),
+                secure: c.secure,
+                http_only: c.http_only,
+            })
+        })
+        .count();
+    debug!("Imported {} cookies from browser", imported);
+    Ok(())
+}
+
+/// 会话 Cookie 转换为 CDP 参数
+///
+/// 主机限定 (host-only) 的 Cookie 以 `url` 指定，避免被浏览器扩展为域 Cookie。
+fn cookie_param(spec: CookieSpec) -> CookieParam {
+    let mut param = CookieParam::new(spec.name, spec.value);
+    if spec.domain.starts_with('.') {
+        param.domain = Some(spec.domain);
+    } else {
+        let scheme = if spec.secure { "https" } else { "http" };
+        param.url = Some(format!("{}://{}{}", scheme, spec.domain, spec.path));
+    }
+    param.path = Some(spec.path);
+    param.secure = Some(spec.secure);
+    param.http_only = Some(spec.http_only);
+    param.expires = spec.expires.map(|ts| TimeSinceEpoch::new(ts as f64));
+    param
+}
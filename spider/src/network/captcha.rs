@@ -0,0 +1,628 @@
+//! 验证码识别器链 (CAPTCHA Solver Chain)
+//!
+//! 提供通用的远程识别与人工输入识别器，并按配置顺序串联站点本地 OCR，记录各识别器的采纳率。
+
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use base64::prelude::*;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::core::config::CaptchaConfig;
+use crate::core::error::{Result, SpiderError};
+use crate::interfaces::captcha::{CaptchaSolution, CaptchaSolver};
+use crate::utils::unix_now;
+
+/// 远程识别服务请求体
+#[derive(Serialize)]
+struct SolveRequest<'a> {
+    /// Base64 编码的图像
+    image: &'a str,
+}
+
+/// 远程识别服务响应体
+#[derive(Deserialize)]
+struct SolveResponse {
+    code: String,
+    /// 未提供时视为完全可信
+    confidence: Option<f32>,
+}
+
+/// 远程识别服务 (HTTP Solver)
+///
+/// 以 `POST {"image": "<base64>"}` 调用识别端点，期望返回 `{"code": "...", "confidence": 0.9}`。
+#[derive(Debug)]
+pub struct HttpSolver {
+    endpoint: String,
+    api_key: Option<String>,
+    client: reqwest::Client,
+}
+
+impl HttpSolver {
+    pub fn new(endpoint: String, api_key: Option<String>) -> Self {
+        Self {
+            endpoint,
+            api_key,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl CaptchaSolver for HttpSolver {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    async fn solve(&self, image: &[u8]) -> Result<CaptchaSolution> {
+        let encoded = BASE64_STANDARD.encode(image);
+        let mut req = self
+            .client
+            .post(&self.endpoint)
+            .json(&SolveRequest { image: &encoded });
+        if let Some(key) = &self.api_key {
+            req = req.bearer_auth(key);
+        }
+
+        let resp: SolveResponse = req
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(SpiderError::Network)?
+            .json()
+            .await
+            .map_err(SpiderError::Network)?;
+
+        Ok(CaptchaSolution {
+            code: resp.code.trim().to_string(),
+            confidence: resp.confidence.unwrap_or(1.0),
+        })
+    }
+}
+
+/// 人工输入识别器 (Manual Solver)
+///
+/// 将图像保存至缓存目录并在终端提示操作员输入；非交互式终端下直接跳过。
+#[derive(Debug)]
+pub struct ManualSolver {
+    /// 图像保存目录
+    dir: PathBuf,
+    /// 等待输入的时长
+    timeout: Duration,
+    /// 未完成的终端读取 (超时后保留，避免多个读取线程争抢输入)
+    pending: tokio::sync::Mutex<Option<JoinHandle<std::io::Result<String>>>>,
+}
+
+impl ManualSolver {
+    pub fn new(cache_path: &str, timeout: Duration) -> Self {
+        Self {
+            dir: PathBuf::from(cache_path).join("captcha"),
+            timeout,
+            pending: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// 依据文件头推断图像扩展名
+    fn extension(image: &[u8]) -> &'static str {
+        match image {
+            [0x89, b'P', b'N', b'G', ..] => "png",
+            [0xFF, 0xD8, ..] => "jpg",
+            [b'G', b'I', b'F', ..] => "gif",
+            _ => "bin",
+        }
+    }
+}
+
+#[async_trait]
+impl CaptchaSolver for ManualSolver {
+    fn name(&self) -> &str {
+        "manual"
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    async fn solve(&self, image: &[u8]) -> Result<CaptchaSolution> {
+        if !std::io::stdin().is_terminal() {
+            return Err(SpiderError::Custom(
+                "Manual CAPTCHA entry requires an interactive terminal".into(),
+            ));
+        }
+
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let path = self.dir.join(format!("{}.{}", ts, Self::extension(image)));
+        tokio::fs::write(&path, image).await?;
+
+        eprintln!(
+            "\nCAPTCHA saved to {}\nEnter the code shown in the image: ",
+            path.display()
+        );
+
+        let mut pending = self.pending.lock().await;
+        let reader = pending.get_or_insert_with(|| {
+            tokio::task::spawn_blocking(|| {
+                let mut line = String::new();
+                std::io::stdin().read_line(&mut line).map(|_| line)
+            })
+        });
+        let line = reader.await;
+        *pending = None;
+        let line = line.map_err(|_| SpiderError::Custom("Terminal reader panicked".into()))??;
+
+        let code = line.trim().to_string();
+        if code.is_empty() {
+            return Err(SpiderError::CaptchaFailed);
+        }
+
+        Ok(CaptchaSolution {
+            code,
+            confidence: 1.0,
+        })
+    }
+}
+
+/// 单个识别器的输出
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Candidate {
+    pub solver: String,
+    #[serde(flatten)]
+    pub solution: CaptchaSolution,
+}
+
+/// 一次识别的全部输出
+#[derive(Debug, Default)]
+pub struct SolveAttempt {
+    /// 按调用顺序排列的识别结果 (含置信度不足者)
+    pub candidates: Vec<Candidate>,
+    /// 被采纳的结果下标
+    pub chosen: Option<usize>,
+}
+
+impl SolveAttempt {
+    /// 被采纳的识别结果
+    pub fn chosen(&self) -> Option<&Candidate> {
+        self.chosen.map(|i| &self.candidates[i])
+    }
+}
+
+/// 识别器统计
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SolverStats {
+    /// 调用次数
+    pub attempts: u32,
+    /// 返回可采纳结果的次数
+    pub solved: u32,
+    /// 提交后校验通过的次数
+    pub accepted: u32,
+}
+
+impl SolverStats {
+    /// 提交通过率
+    pub fn success_rate(&self) -> f32 {
+        if self.solved == 0 {
+            0.0
+        } else {
+            self.accepted as f32 / self.solved as f32
+        }
+    }
+}
+
+/// 识别器链 (Solver Chain)
+///
+/// 依次调用各识别器，返回首个置信度达标的结果。
+#[derive(Debug)]
+pub struct SolverChain {
+    solvers: Vec<Arc<dyn CaptchaSolver>>,
+    min_confidence: f32,
+    stats: Mutex<HashMap<String, SolverStats>>,
+}
+
+impl SolverChain {
+    pub fn new(solvers: Vec<Arc<dyn CaptchaSolver>>, min_confidence: f32) -> Self {
+        Self {
+            solvers,
+            min_confidence,
+            stats: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 按配置顺序装配识别器链
+    ///
+    /// `local` 为站点提供的本地 OCR (对应 `ocr`)；未配置端点的 `http` 与未知名称被忽略。
+    pub fn from_config(
+        config: &CaptchaConfig,
+        local: Option<Arc<dyn CaptchaSolver>>,
+        cache_path: &str,
+    ) -> Self {
+        let solvers = config
+            .solvers
+            .iter()
+            .filter_map(|name| -> Option<Arc<dyn CaptchaSolver>> {
+                match name.as_str() {
+                    "ocr" => local.clone(),
+                    "http" => config.http_endpoint.clone().map(|endpoint| {
+                        Arc::new(HttpSolver::new(endpoint, config.http_api_key.clone())) as _
+                    }),
+                    "manual" => Some(Arc::new(ManualSolver::new(
+                        cache_path,
+                        Duration::from_secs(config.manual_timeout_secs),
+                    ))),
+                    other => {
+                        warn!("Unknown CAPTCHA solver ignored: {}", other);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Self::new(solvers, config.min_confidence)
+    }
+
+    /// 依次识别，直至某识别器给出置信度达标的结果
+    pub async fn solve(&self, image: &[u8]) -> SolveAttempt {
+        let mut attempt = SolveAttempt::default();
+
+        for solver in &self.solvers {
+            let name = solver.name().to_string();
+            self.stats.lock().entry(name.clone()).or_default().attempts += 1;
+
+            let solution = match tokio::time::timeout(solver.timeout(), solver.solve(image)).await {
+                Ok(Ok(solution)) => solution,
+                Ok(Err(e)) => {
+                    debug!("CAPTCHA solver {} failed: {}", name, e);
+                    continue;
+                }
+                Err(_) => {
+                    debug!("CAPTCHA solver {} timed out", name);
+                    continue;
+                }
+            };
+
+            let confident = !solution.code.is_empty() && solution.confidence >= self.min_confidence;
+            attempt.candidates.push(Candidate {
+                solver: name.clone(),
+                solution,
+            });
+
+            if !confident {
+                let solution = &attempt
+                    .candidates
+                    .last()
+                    .expect("candidate pushed")
+                    .solution;
+                debug!(
+                    "CAPTCHA solver {} low confidence ({:.2} < {:.2}): {}",
+                    name, solution.confidence, self.min_confidence, solution.code
+                );
+                continue;
+            }
+
+            self.stats.lock().entry(name).or_default().solved += 1;
+            attempt.chosen = Some(attempt.candidates.len() - 1);
+            break;
+        }
+
+        attempt
+    }
+
+    /// 记录提交校验结果
+    pub fn record(&self, solver: &str, accepted: bool) {
+        let mut stats = self.stats.lock();
+        let entry = stats.entry(solver.to_string()).or_default();
+        if accepted {
+            entry.accepted += 1;
+        }
+        info!(
+            "CAPTCHA solver {}: {}/{} accepted ({:.0}%)",
+            solver,
+            entry.accepted,
+            entry.solved,
+            entry.success_rate() * 100.0
+        );
+    }
+
+    /// 各识别器统计快照
+    pub fn stats(&self) -> HashMap<String, SolverStats> {
+        self.stats.lock().clone()
+    }
+}
+
+/// 样本校验结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SampleOutcome {
+    /// 提交通过 (答案即真实标签)
+    Accepted,
+    /// 提交被拒
+    Rejected,
+    /// 无识别器给出可采纳结果，未提交
+    Unsolved,
+}
+
+/// 样本清单条目 (`manifest.jsonl` 每行一条)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampleRecord {
+    /// 图像文件名 (内容哈希，重复图像共用同一文件)
+    pub file: String,
+    /// 记录时间 (Unix 秒)
+    pub timestamp: u64,
+    /// 各识别器输出
+    pub candidates: Vec<Candidate>,
+    /// 提交的识别器
+    pub solver: Option<String>,
+    /// 提交的答案
+    pub answer: Option<String>,
+    /// 校验结果
+    pub outcome: SampleOutcome,
+}
+
+impl SampleRecord {
+    /// 指定识别器的输出
+    pub fn candidate(&self, solver: &str) -> Option<&CaptchaSolution> {
+        self.candidates
+            .iter()
+            .find(|c| c.solver == solver)
+            .map(|c| &c.solution)
+    }
+
+    /// 经站点校验的真实标签
+    pub fn label(&self) -> Option<&str> {
+        match self.outcome {
+            SampleOutcome::Accepted => self.answer.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// 验证码样本记录器 (Dataset Recorder)
+///
+/// 将图像与识别结果、校验结果写入 `cache/captcha/<site>/`，供后续训练 OCR 模型。
+#[derive(Debug)]
+pub struct SampleRecorder {
+    dir: PathBuf,
+}
+
+impl SampleRecorder {
+    /// 清单文件名
+    pub const MANIFEST: &'static str = "manifest.jsonl";
+
+    pub fn new(cache_path: &str, site_id: &str) -> Self {
+        Self {
+            dir: Self::site_dir(cache_path, site_id),
+        }
+    }
+
+    /// 站点样本目录
+    pub fn site_dir(cache_path: &str, site_id: &str) -> PathBuf {
+        PathBuf::from(cache_path).join("captcha").join(site_id)
+    }
+
+    /// 保存样本并追加清单条目
+    pub async fn record(
+        &self,
+        image: &[u8],
+        attempt: &SolveAttempt,
+        outcome: SampleOutcome,
+    ) -> Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+
+        let file = format!(
+            "{}.{}",
+            &blake3::hash(image).to_hex()[..16],
+            ManualSolver::extension(image)
+        );
+        let path = self.dir.join(&file);
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            tokio::fs::write(&path, image).await?;
+        }
+
+        let chosen = attempt.chosen();
+        let record = SampleRecord {
+            file,
+            timestamp: unix_now(),
+            candidates: attempt.candidates.clone(),
+            solver: chosen.map(|c| c.solver.clone()),
+            answer: chosen.map(|c| c.solution.code.clone()),
+            outcome,
+        };
+
+        let mut line = serde_json::to_string(&record)?;
+        line.push('\n');
+
+        let mut manifest = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(Self::MANIFEST))
+            .await?;
+        manifest.write_all(line.as_bytes()).await?;
+        manifest.flush().await?;
+        Ok(())
+    }
+
+    /// 读取样本清单 (跳过无法解析的行)
+    pub fn load(dir: &Path) -> Result<Vec<SampleRecord>> {
+        let text = match std::fs::read_to_string(dir.join(Self::MANIFEST)) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(text
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .filter_map(|l| serde_json::from_str(l).ok())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::cassette::testing::temp_dir;
+
+    /// 固定输出的识别器，`delay` 超过超时上限时模拟卡住的识别器
+    #[derive(Debug)]
+    struct Stub {
+        name: &'static str,
+        code: &'static str,
+        confidence: f32,
+        delay: Duration,
+    }
+
+    impl Stub {
+        fn answer(
+            name: &'static str,
+            code: &'static str,
+            confidence: f32,
+        ) -> Arc<dyn CaptchaSolver> {
+            Arc::new(Self {
+                name,
+                code,
+                confidence,
+                delay: Duration::ZERO,
+            })
+        }
+
+        fn stuck(name: &'static str) -> Arc<dyn CaptchaSolver> {
+            Arc::new(Self {
+                name,
+                code: "late",
+                confidence: 1.0,
+                delay: Duration::from_secs(5),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl CaptchaSolver for Stub {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn timeout(&self) -> Duration {
+            Duration::from_millis(20)
+        }
+
+        async fn solve(&self, _image: &[u8]) -> Result<CaptchaSolution> {
+            tokio::time::sleep(self.delay).await;
+            Ok(CaptchaSolution {
+                code: self.code.to_string(),
+                confidence: self.confidence,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn low_confidence_falls_through_to_next_solver() {
+        let chain = SolverChain::new(
+            vec![
+                Stub::answer("ocr", "1234", 0.3),
+                Stub::answer("http", "5678", 0.9),
+            ],
+            0.6,
+        );
+
+        let attempt = chain.solve(b"image").await;
+        let solvers: Vec<_> = attempt
+            .candidates
+            .iter()
+            .map(|c| c.solver.as_str())
+            .collect();
+        assert_eq!(solvers, ["ocr", "http"]);
+        let chosen = attempt.chosen().unwrap();
+        assert_eq!(
+            (chosen.solver.as_str(), chosen.solution.code.as_str()),
+            ("http", "5678")
+        );
+
+        let stats = chain.stats();
+        assert_eq!((stats["ocr"].attempts, stats["ocr"].solved), (1, 0));
+        assert_eq!((stats["http"].attempts, stats["http"].solved), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn timed_out_solver_is_skipped() {
+        let chain = SolverChain::new(
+            vec![Stub::stuck("http"), Stub::answer("manual", "abcd", 1.0)],
+            0.6,
+        );
+
+        let attempt = chain.solve(b"image").await;
+        assert_eq!(attempt.candidates.len(), 1);
+        assert_eq!(attempt.chosen().unwrap().solver, "manual");
+        assert_eq!(chain.stats()["http"].attempts, 1);
+
+        // 所有识别器均无可采纳结果时不选中任何候选
+        let chain = SolverChain::new(vec![Stub::stuck("http"), Stub::answer("ocr", "", 1.0)], 0.6);
+        let attempt = chain.solve(b"image").await;
+        assert!(attempt.chosen().is_none());
+        assert_eq!(attempt.candidates.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn success_rate_counts_accepted_submissions() {
+        let chain = SolverChain::new(vec![Stub::answer("ocr", "1234", 0.9)], 0.6);
+        for accepted in [true, false, true, true] {
+            let attempt = chain.solve(b"image").await;
+            chain.record(&attempt.chosen().unwrap().solver, accepted);
+        }
+
+        let stats = chain.stats()["ocr"];
+        assert_eq!((stats.attempts, stats.solved, stats.accepted), (4, 4, 3));
+        assert_eq!(stats.success_rate(), 0.75);
+        assert_eq!(SolverStats::default().success_rate(), 0.0);
+    }
+
+    #[tokio::test]
+    async fn recorder_shares_image_file_and_appends_manifest() {
+        let dir = temp_dir("captcha-samples");
+        let recorder = SampleRecorder::new(dir.to_str().unwrap(), "site");
+        let chain = SolverChain::new(vec![Stub::answer("ocr", "1234", 0.9)], 0.6);
+        let image = b"\x89PNG\r\n\x1a\nsample";
+
+        let attempt = chain.solve(image).await;
+        recorder
+            .record(image, &attempt, SampleOutcome::Accepted)
+            .await
+            .unwrap();
+        recorder
+            .record(image, &attempt, SampleOutcome::Rejected)
+            .await
+            .unwrap();
+
+        let site_dir = SampleRecorder::site_dir(dir.to_str().unwrap(), "site");
+        let mut files: Vec<_> = std::fs::read_dir(&site_dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        assert_eq!(files.len(), 2);
+        assert!(files[0].ends_with(".png"));
+        assert_eq!(files[1], SampleRecorder::MANIFEST);
+        assert_eq!(std::fs::read(site_dir.join(&files[0])).unwrap(), image);
+
+        let records = SampleRecorder::load(&site_dir).unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|r| r.file == files[0]));
+        assert_eq!(records[0].outcome, SampleOutcome::Accepted);
+        assert_eq!(records[0].label(), Some("1234"));
+        assert_eq!(records[1].answer.as_deref(), Some("1234"));
+        assert_eq!(records[1].label(), None);
+        assert_eq!(records[1].candidate("ocr").unwrap().code, "1234");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,417 @@
+//! HTTP 录制与回放 (Record / Replay Cassettes)
+//!
+//! 录制模式下将每次请求/响应对保存至录音带目录；回放模式下仅从录音带返回响应，
+//! 不访问网络与代理，用于离线、可重复的站点测试。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use base64::prelude::*;
+use http::Extensions;
+use parking_lot::Mutex;
+use reqwest::header::{CONTENT_ENCODING, CONTENT_LENGTH, HeaderMap, TRANSFER_ENCODING};
+use reqwest::{Request, Response, ResponseBuilderExt, StatusCode};
+use reqwest_middleware::{Middleware, Next};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use url::Url;
+
+use crate::core::config::{CassetteConfig, CassetteMode};
+use crate::core::error::{Result, SpiderError};
+use crate::network::{OriginalUrl, ResponseMetadata};
+
+/// 单次请求/响应记录
+///
+/// 请求体仅保存摘要 (登录表单含密码)，用于区分同一 URL 的不同提交。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub method: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_hash: Option<String>,
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    /// UTF-8 响应体
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// 非 UTF-8 响应体 (Base64)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_base64: Option<String>,
+}
+
+impl Interaction {
+    /// 构造响应记录 (回放测试亦可直接手写)
+    pub fn new(method: &str, url: &str, status: u16, body: &[u8]) -> Self {
+        let mut interaction = Self {
+            method: method.to_string(),
+            url: url.to_string(),
+            body_hash: None,
+            status,
+            headers: Vec::new(),
+            body: None,
+            body_base64: None,
+        };
+        interaction.set_body(body);
+        interaction
+    }
+
+    /// 追加响应头
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    fn set_body(&mut self, body: &[u8]) {
+        match std::str::from_utf8(body) {
+            Ok(text) => self.body = Some(text.to_string()),
+            Err(_) => self.body_base64 = Some(BASE64_STANDARD.encode(body)),
+        }
+    }
+
+    fn body_bytes(&self) -> Result<Vec<u8>> {
+        if let Some(encoded) = &self.body_base64 {
+            return BASE64_STANDARD
+                .decode(encoded)
+                .map_err(|e| SpiderError::Parse(format!("cassette body: {}", e)));
+        }
+        Ok(self.body.clone().unwrap_or_default().into_bytes())
+    }
+
+    /// 还原为 `Response`
+    pub(crate) fn to_response(&self) -> Result<Response> {
+        let url = Url::parse(&self.url)
+            .map_err(|e| SpiderError::Parse(format!("cassette url {}: {}", self.url, e)))?;
+        let status = StatusCode::from_u16(self.status)
+            .map_err(|e| SpiderError::Parse(format!("cassette status: {}", e)))?;
+
+        let mut builder = http::Response::builder().status(status).url(url);
+        for (name, value) in &self.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        let http_resp = builder
+            .body(self.body_bytes()?)
+            .map_err(|e| SpiderError::Parse(format!("cassette response: {}", e)))?;
+
+        let mut resp = Response::from(http_resp);
+        resp.extensions_mut().insert(OriginalUrl(self.url.clone()));
+        Ok(resp)
+    }
+}
+
+/// 请求指纹 (方法 + URL + 请求体摘要)
+fn request_key(method: &str, url: &str, body_hash: Option<&str>) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(method.as_bytes());
+    hasher.update(b" ");
+    hasher.update(url.as_bytes());
+    if let Some(hash) = body_hash {
+        hasher.update(b" ");
+        hasher.update(hash.as_bytes());
+    }
+    hasher.finalize().to_hex()[..16].to_string()
+}
+
+fn body_hash(body: Option<&[u8]>) -> Option<String> {
+    body.filter(|b| !b.is_empty())
+        .map(|b| blake3::hash(b).to_hex()[..16].to_string())
+}
+
+/// 同一请求的录制序列及回放游标
+#[derive(Default)]
+struct Track {
+    interactions: Vec<Interaction>,
+    cursor: usize,
+}
+
+/// 录音带存储 (Cassette Store)
+///
+/// 每个请求指纹对应 `<dir>/<host>/<key>.json`，按请求发生顺序保存响应序列；
+/// 回放时依序返回，序列耗尽后重复最后一条 (轮询类请求)。
+pub struct Cassette {
+    dir: PathBuf,
+    mode: CassetteMode,
+    tracks: Mutex<HashMap<String, Track>>,
+}
+
+impl Cassette {
+    pub fn new(dir: impl Into<PathBuf>, mode: CassetteMode) -> Self {
+        Self {
+            dir: dir.into(),
+            mode,
+            tracks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 按配置创建，关闭时返回 `None`
+    pub fn from_config(config: &CassetteConfig) -> Option<Self> {
+        (config.mode != CassetteMode::Off).then(|| Self::new(&config.dir, config.mode))
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    fn track_path(&self, url: &str, key: &str) -> PathBuf {
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_else(|| "_".to_string());
+        self.dir.join(host).join(format!("{}.json", key))
+    }
+
+    fn load_track(path: &Path) -> Result<Vec<Interaction>> {
+        let data = std::fs::read(path)?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    /// 追加一条录制记录
+    ///
+    /// 本次运行首次写入某请求时覆盖旧文件，使重新录制不会与过期数据混杂。
+    pub fn record(&self, interaction: Interaction) -> Result<()> {
+        let key = request_key(
+            &interaction.method,
+            &interaction.url,
+            interaction.body_hash.as_deref(),
+        );
+        let path = self.track_path(&interaction.url, &key);
+
+        let mut tracks = self.tracks.lock();
+        let track = tracks.entry(key).or_default();
+        track.interactions.push(interaction);
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_vec_pretty(&track.interactions)?)?;
+        Ok(())
+    }
+
+    /// 取出下一条回放记录
+    pub fn replay(&self, method: &str, url: &str, body_hash: Option<&str>) -> Result<Interaction> {
+        let key = request_key(method, url, body_hash);
+        let mut tracks = self.tracks.lock();
+
+        if !tracks.contains_key(&key) {
+            let path = self.track_path(url, &key);
+            let interactions = Self::load_track(&path).map_err(|e| {
+                SpiderError::Custom(format!(
+                    "No cassette entry for {} {} ({}: {})",
+                    method,
+                    url,
+                    path.display(),
+                    e
+                ))
+            })?;
+            tracks.insert(
+                key.clone(),
+                Track {
+                    interactions,
+                    cursor: 0,
+                },
+            );
+        }
+
+        let track = tracks.get_mut(&key).expect("track inserted above");
+        let index = track.cursor.min(track.interactions.len().saturating_sub(1));
+        let interaction = track.interactions.get(index).cloned().ok_or_else(|| {
+            SpiderError::Custom(format!("Empty cassette entry for {} {}", method, url))
+        })?;
+        track.cursor += 1;
+        Ok(interaction)
+    }
+}
+
+impl std::fmt::Debug for Cassette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cassette")
+            .field("dir", &self.dir)
+            .field("mode", &self.mode)
+            .finish()
+    }
+}
+
+/// 录制/回放中间件
+///
+/// 位于中间件链最内层，使回放响应同样经过会话同步与反爬策略。
+pub struct CassetteMiddleware(pub std::sync::Arc<Cassette>);
+
+impl CassetteMiddleware {
+    async fn record(
+        &self,
+        resp: Response,
+        method: String,
+        hash: Option<String>,
+    ) -> Result<Response> {
+        let mut metadata = ResponseMetadata::from_response(&resp);
+        let bytes = resp.bytes().await.map_err(SpiderError::Network)?;
+
+        // 响应体已解压，移除与原始传输相关的头部
+        for name in [CONTENT_ENCODING, CONTENT_LENGTH, TRANSFER_ENCODING] {
+            metadata.headers.remove(name);
+        }
+
+        let mut interaction =
+            Interaction::new(&method, &metadata.url, metadata.status.as_u16(), &bytes);
+        interaction.body_hash = hash;
+        interaction.headers = header_pairs(&metadata.headers);
+        self.0.record(interaction)?;
+
+        Ok(metadata.rebuild(bytes))
+    }
+}
+
+fn header_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(k, v)| v.to_str().ok().map(|v| (k.to_string(), v.to_string())))
+        .collect()
+}
+
+#[async_trait::async_trait]
+impl Middleware for CassetteMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let method = req.method().to_string();
+        let url = req.url().to_string();
+        let hash = body_hash(req.body().and_then(|b| b.as_bytes()));
+
+        match self.0.mode() {
+            CassetteMode::Replay => {
+                debug!("Replaying {} {}", method, url);
+                let resp = self
+                    .0
+                    .replay(&method, &url, hash.as_deref())
+                    .and_then(|i| i.to_response())
+                    .map_err(anyhow::Error::from)?;
+                Ok(resp)
+            }
+            CassetteMode::Record => {
+                let resp = next.run(req, extensions).await?;
+                debug!("Recording {} {}", method, url);
+                Ok(self
+                    .record(resp, method, hash)
+                    .await
+                    .map_err(anyhow::Error::from)?)
+            }
+            CassetteMode::Off => next.run(req, extensions).await,
+        }
+    }
+}
+
+/// 回放测试支撑 (Replay Test Harness)
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+
+    use crate::core::config::{AppConfig, CassetteMode};
+    use crate::network::browser::BrowserService;
+    use crate::network::context::ServiceContext;
+    use crate::network::service::HttpService;
+
+    /// 每个测试独立的临时目录
+    pub fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("spider-cassette-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    /// 构造回放模式的服务上下文 (无网络、无代理)
+    pub fn replay_context(dir: &Path) -> ServiceContext {
+        let mut config = AppConfig {
+            cache_path: dir.join("cache").to_string_lossy().into_owned(),
+            ..AppConfig::default()
+        };
+        config.set_cassette(
+            CassetteMode::Replay,
+            Some(dir.to_string_lossy().into_owned()),
+        );
+        let config = Arc::new(config);
+
+        let (proxy_tx, _proxy_rx) = flume::unbounded();
+        ServiceContext::new(
+            Arc::new(HttpService::new(config.clone())),
+            proxy_tx,
+            Arc::new(BrowserService::new(config.clone())),
+            config,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::temp_dir;
+    use super::*;
+
+    #[test]
+    fn replays_recorded_sequence_in_order() {
+        let dir = temp_dir("sequence");
+        let url = "https://example.com/novel/1";
+
+        let recorder = Cassette::new(&dir, CassetteMode::Record);
+        recorder
+            .record(Interaction::new("GET", url, 503, b"wait"))
+            .unwrap();
+        recorder
+            .record(Interaction::new("GET", url, 200, b"ok").with_header("x-test", "1"))
+            .unwrap();
+
+        let player = Cassette::new(&dir, CassetteMode::Replay);
+        let first = player.replay("GET", url, None).unwrap();
+        let second = player.replay("GET", url, None).unwrap();
+        let third = player.replay("GET", url, None).unwrap();
+        assert_eq!(first.status, 503);
+        assert_eq!(second.status, 200);
+        assert_eq!(third.status, 200, "exhausted track repeats the last entry");
+        assert_eq!(second.headers, vec![("x-test".into(), "1".into())]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn distinguishes_method_and_body() {
+        let dir = temp_dir("body");
+        let url = "https://example.com/bbs/login_check.php";
+        let hash = body_hash(Some(b"mb_id=a")).unwrap();
+
+        let recorder = Cassette::new(&dir, CassetteMode::Record);
+        let mut post = Interaction::new("POST", url, 302, b"");
+        post.body_hash = Some(hash.clone());
+        recorder.record(post).unwrap();
+
+        let player = Cassette::new(&dir, CassetteMode::Replay);
+        assert_eq!(player.replay("POST", url, Some(&hash)).unwrap().status, 302);
+        assert!(player.replay("GET", url, None).is_err());
+        assert!(player.replay("POST", url, Some("other")).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn binary_bodies_round_trip() {
+        let body = [0x89, b'P', b'N', b'G', 0xff, 0x00];
+        let interaction = Interaction::new("GET", "https://example.com/a.png", 200, &body);
+        assert!(interaction.body.is_none());
+        assert_eq!(interaction.body_bytes().unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn rebuilt_response_keeps_url_and_headers() {
+        let url = "https://example.com/path?q=1";
+        let resp = Interaction::new("GET", url, 404, b"missing")
+            .with_header("content-type", "text/html")
+            .to_response()
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(resp.url().as_str(), url);
+        assert_eq!(resp.headers()["content-type"], "text/html");
+        assert_eq!(resp.text().await.unwrap(), "missing");
+    }
+}
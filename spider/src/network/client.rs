@@ -2,12 +2,52 @@
 //!
 //! 提供面向具体站点的语义化请求接口，并注入特定拦截策略链。
 
-use crate::core::error::Result;
+use crate::core::config::CassetteMode;
+use crate::core::error::{Result, SpiderError};
 use crate::interfaces::NetworkPolicy;
+use crate::network::ResponseExt;
+use crate::network::browser::WaitCondition;
 use crate::network::context::ServiceContext;
+use crate::network::domain::DomainResolver;
 use crate::network::session::Session;
-use reqwest::Response;
+use crate::network::snapshot::SnapshotStore;
+use reqwest::header::HeaderMap;
+use reqwest::{Response, StatusCode};
+use scraper::Selector;
+use scraper::selector::ToCss;
+use std::collections::HashMap;
 use std::sync::Arc;
+use url::Url;
+
+/// 已获取的页面
+///
+/// `url` 为跟随重定向后的最终地址，页面内相对链接应以其为基准解析。
+#[derive(Debug, Clone)]
+pub struct Page {
+    /// 最终地址
+    pub url: Url,
+    /// 响应状态码
+    pub status: StatusCode,
+    /// 响应头部
+    pub headers: HeaderMap,
+    /// 响应体文本
+    pub text: String,
+}
+
+/// 页面请求类别
+///
+/// 站点可按类别选择以浏览器渲染代替普通 HTTP 请求。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RequestKind {
+    /// 书籍详情页
+    Metadata,
+    /// 章节目录页
+    ChapterList,
+    /// 章节正文页
+    Content,
+    /// 检索结果页
+    Search,
+}
 
 /// 站点特定的逻辑客户端
 #[derive(Clone)]
@@ -16,19 +56,67 @@ pub struct SiteClient {
     pub ctx: ServiceContext,
     /// 绑定的网络策略集合
     pub policies: Vec<Arc<dyn NetworkPolicy>>,
+    /// 镜像域名解析器 (可选)
+    pub domain: Option<Arc<DomainResolver>>,
+    /// 启用浏览器渲染的请求类别及其等待条件
+    pub render: HashMap<RequestKind, WaitCondition>,
 }
 
 impl SiteClient {
     pub fn new(ctx: ServiceContext, policies: Vec<Arc<dyn NetworkPolicy>>) -> Self {
-        Self { ctx, policies }
+        Self {
+            ctx,
+            policies,
+            domain: None,
+            render: HashMap::new(),
+        }
+    }
+
+    /// 绑定镜像域名解析器，启用 URL 改写与迁移跟随
+    pub fn with_domain(mut self, domain: Arc<DomainResolver>) -> Self {
+        self.domain = Some(domain);
+        self
+    }
+
+    /// 指定类别的页面改由浏览器渲染获取
+    pub fn with_render(mut self, kind: RequestKind, wait: WaitCondition) -> Self {
+        self.render.insert(kind, wait);
+        self
+    }
+
+    fn rebase(&self, url: &str) -> String {
+        match &self.domain {
+            Some(domain) => domain.rebase(url),
+            None => url.to_string(),
+        }
     }
 
     /// 执行通用 HTTP GET 请求
+    ///
+    /// 旧镜像域名的 URL 会被改写至当前域名；重定向至新镜像域名时更新站点域名。
     pub async fn get(&self, url: &str) -> Result<Response> {
-        self.ctx
+        let url = self.rebase(url);
+
+        let result = self
+            .ctx
             .http
-            .execute(&self.ctx, reqwest::Method::GET, url, self.policies.clone())
-            .await
+            .execute(&self.ctx, reqwest::Method::GET, &url, self.policies.clone())
+            .await;
+
+        if let Some(domain) = &self.domain {
+            match &result {
+                Ok(resp) => {
+                    domain.adopt(&self.ctx, resp.original_url()).await;
+                }
+                Err(e) => {
+                    if let Some(target) = e.redirect_target() {
+                        domain.adopt(&self.ctx, target).await;
+                    }
+                }
+            }
+        }
+
+        result
     }
 
     /// 提取响应体文本
@@ -41,6 +129,77 @@ impl SiteClient {
         Ok(text)
     }
 
+    /// 获取页面文本及其最终地址
+    pub async fn get_page(&self, url: &str) -> Result<Page> {
+        let resp = self.get(url).await?;
+        let final_url = Url::parse(resp.original_url())
+            .map_err(|e| crate::core::error::SpiderError::Custom(format!("Invalid URL: {}", e)))?;
+        let status = resp.status();
+        let headers = resp.headers().clone();
+        let text = resp
+            .text()
+            .await
+            .map_err(crate::core::error::SpiderError::Network)?;
+        Ok(Page {
+            url: final_url,
+            status,
+            headers,
+            text,
+        })
+    }
+
+    /// 经浏览器渲染获取页面 (适用于客户端脚本生成内容的站点)
+    ///
+    /// 与 HTTP 请求共享会话 Cookie、UA 及本地代理；不经过网络策略链。
+    pub async fn get_rendered(&self, url: &str, wait: &WaitCondition) -> Result<Page> {
+        let url = self.rebase(url);
+        let page = self
+            .ctx
+            .browser
+            .fetch_rendered(&url, wait, &self.ctx)
+            .await?;
+
+        if let Some(domain) = &self.domain {
+            domain.adopt(&self.ctx, page.url.as_str()).await;
+        }
+
+        Ok(page)
+    }
+
+    /// 按请求类别获取页面
+    ///
+    /// 该类别启用了浏览器渲染时走 `get_rendered`，否则为普通 `get_page`。
+    /// 录制/回放模式下浏览器流量无法入带，统一走 `get_page`。
+    pub async fn fetch_page(&self, kind: RequestKind, url: &str) -> Result<Page> {
+        match self.render.get(&kind) {
+            Some(wait) if self.ctx.config.cassette.mode == CassetteMode::Off => {
+                self.get_rendered(url, wait).await
+            }
+            _ => self.get_page(url).await,
+        }
+    }
+
+    /// 构造页面解析失败错误
+    ///
+    /// 按配置保存调试快照，快照路径附于错误信息中。
+    pub fn parse_error(&self, page: &Page, selector: &Selector, message: &str) -> SpiderError {
+        let selector = selector.to_css_string();
+        let store = SnapshotStore::new(&self.ctx.config.cache_path, &self.ctx.config.debug);
+        match store.save(page, &selector, message) {
+            Some(dir) => SpiderError::Parse(format!(
+                "{} at {} (selector: {}, snapshot: {})",
+                message,
+                page.url,
+                selector,
+                dir.display()
+            )),
+            None => SpiderError::Parse(format!(
+                "{} at {} (selector: {})",
+                message, page.url, selector
+            )),
+        }
+    }
+
     /// 提取二进制负载
     pub async fn get_bytes(&self, url: &str) -> Result<Vec<u8>> {
         let resp = self.get(url).await?;
@@ -53,6 +212,6 @@ impl SiteClient {
 
     /// 获取当前会话状态句柄
     pub fn session(&self) -> &Session {
-        &self.ctx.session
+        self.ctx.session()
     }
 }
@@ -6,6 +6,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use flume::Sender;
+use parking_lot::RwLock;
 use rand::Rng;
 use reqwest::Method;
 use reqwest_middleware::RequestBuilder;
@@ -18,6 +19,8 @@ use crate::core::coordinator::Coordinator;
 use crate::core::error::{BlockReason, Result, SpiderError};
 use crate::core::event::{EventSender, SpiderEvent};
 use crate::network::browser::BrowserService;
+use crate::network::cookie::CookieLocation;
+use crate::network::egress;
 use crate::network::service::HttpService;
 use crate::network::session::Session;
 
@@ -28,8 +31,8 @@ use crate::network::session::Session;
 pub struct ServiceContext {
     /// 核心 HTTP 引擎
     pub http: Arc<HttpService>,
-    /// 同步会话状态
-    pub session: Arc<Session>,
+    /// 各出口通道的会话状态 (凭据与通道出口 IP 绑定)
+    sessions: Arc<Vec<Arc<Session>>>,
     /// 代理调度信道
     pub proxy: Sender<ProxyMsg>,
     /// 浏览器自动化服务
@@ -42,6 +45,10 @@ pub struct ServiceContext {
     pub shutdown: CancellationToken,
     /// 事件上报总线
     pub events: Option<EventSender>,
+    /// 当前绑定的站点标识 (用于按站点隔离被封节点)
+    site: Arc<RwLock<Option<String>>>,
+    /// 各出口通道的轮换锁 (多出口时替代全局阻塞)
+    lane_fix: Arc<Vec<tokio::sync::Mutex<()>>>,
 }
 
 impl ServiceContext {
@@ -54,20 +61,29 @@ impl ServiceContext {
 
     pub fn new(
         http: Arc<HttpService>,
-        session: Arc<Session>,
         proxy: Sender<ProxyMsg>,
         browser: Arc<BrowserService>,
         config: Arc<AppConfig>,
     ) -> Self {
         Self {
             http,
-            session,
+            sessions: Arc::new(
+                (0..egress::count(&config))
+                    .map(|_| Arc::new(Session::new()))
+                    .collect(),
+            ),
             proxy,
             browser,
-            config,
             coordinator: Coordinator::new(),
             shutdown: CancellationToken::new(),
             events: None,
+            site: Arc::new(RwLock::new(None)),
+            lane_fix: Arc::new(
+                (0..egress::count(&config))
+                    .map(|_| tokio::sync::Mutex::new(()))
+                    .collect(),
+            ),
+            config,
         }
     }
 
@@ -95,10 +111,20 @@ impl ServiceContext {
     ) -> RequestBuilder {
         client
             .request(method, url)
-            .with_extension(self.session.clone())
+            .with_extension(self.session().clone())
             .with_extension(self.clone())
     }
 
+    /// 当前任务所属出口通道的会话
+    pub fn session(&self) -> &Arc<Session> {
+        &self.sessions[self.lane()]
+    }
+
+    /// 指定出口通道的会话 (如固定经主通道出口的浏览器)
+    pub fn lane_session(&self, lane: usize) -> &Arc<Session> {
+        &self.sessions[lane.min(self.sessions.len() - 1)]
+    }
+
     pub async fn probe(&self, url: &str) -> Result<(u16, String)> {
         self.http.probe(url, self.clone()).await
     }
@@ -190,7 +216,7 @@ impl ServiceContext {
 
         match reason {
             BlockReason::IpBlocked | BlockReason::RateLimit => {
-                self.rotate_proxy().await;
+                self.rotate_proxy(reason).await;
             }
             _ => {}
         }
@@ -237,43 +263,134 @@ impl ServiceContext {
         half + Duration::from_nanos(jitter_nanos)
     }
 
-    pub async fn rotate_proxy(&self) {
+    /// 因阻断切换节点，当前节点对所绑定站点进入冷却
+    ///
+    /// 多出口时仅轮换当前任务所属通道，其他通道上的任务不受阻塞；
+    /// 同一通道的并发阻断只触发一次轮换。
+    pub async fn rotate_proxy(&self, reason: BlockReason) {
+        if self.lane_fix.len() > 1 {
+            let lane = self.lane();
+            match self.lane_fix[lane].try_lock() {
+                Ok(_guard) => self.do_rotate_proxy(lane, reason).await,
+                // 其他任务正在轮换该通道，等待其完成
+                Err(_) => drop(self.lane_fix[lane].lock().await),
+            }
+            return;
+        }
+
         if let Some(_guard) = self
             .coordinator
             .try_acquire_fix(BlockReason::IpBlocked)
             .await
         {
-            self.do_rotate_proxy().await;
+            self.do_rotate_proxy(0, reason).await;
         }
     }
 
-    async fn do_rotate_proxy(&self) {
-        let (tx, rx) = tokio::sync::oneshot::channel();
+    /// 当前任务所属出口通道
+    fn lane(&self) -> usize {
+        egress::current().min(self.lane_fix.len() - 1)
+    }
 
-        if self.proxy.send(ProxyMsg::Rotate { reply: Some(tx) }).is_ok() {
+    async fn do_rotate_proxy(&self, lane: usize, reason: BlockReason) {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let msg = ProxyMsg::Rotate {
+            lane,
+            site: self.site.read().clone(),
+            reason: Some(reason),
+            reply: Some(tx),
+        };
+
+        if self.proxy.send(msg).is_ok() {
             match tokio::time::timeout(Self::PROXY_ROTATION_TIMEOUT, rx).await {
-                Ok(_) => self.on_proxy_rotation_success(),
+                Ok(_) => self.on_proxy_rotation_success(lane).await,
                 Err(_) => warn!("Proxy rotation timeout"),
             }
         }
     }
 
-    fn on_proxy_rotation_success(&self) {
-        debug!("Proxy rotation confirmed by actor");
-        self.session.clear();
+    async fn on_proxy_rotation_success(&self, lane: usize) {
+        debug!("Proxy rotation confirmed by actor (lane {})", lane);
+
+        // 凭据与通道出口 IP 绑定：保存旧节点凭据并切换至新节点的 Cookie Jar
+        let node = self.lane_node(lane).await;
+        if let Err(e) = self.lane_session(lane).switch_node(node) {
+            warn!("Failed to persist session cookies: {}", e);
+        }
 
-        if let Err(e) = self.http.recreate_client() {
+        if let Err(e) = self.http.recreate_client(lane, self.lane_session(lane)) {
             warn!("Failed to recreate client: {}", e);
         }
     }
 
-    pub async fn force_rotate_proxy(&self) {
-        self.do_rotate_proxy().await;
+    /// 立即轮换当前任务所属通道
+    pub async fn force_rotate_proxy(&self, reason: BlockReason) {
+        self.do_rotate_proxy(self.lane(), reason).await;
+    }
+
+    /// 立即轮换指定通道 (如固定经主通道出口的浏览器)
+    pub async fn force_rotate_lane(&self, lane: usize, reason: BlockReason) {
+        self.do_rotate_proxy(lane.min(self.lane_fix.len() - 1), reason)
+            .await;
+    }
+
+    /// 查询当前任务所属通道的出口节点指纹 (直连或查询失败时返回 `None`)
+    pub async fn current_node(&self) -> Option<String> {
+        self.lane_node(self.lane()).await
+    }
+
+    /// 查询指定通道的出口节点指纹
+    async fn lane_node(&self, lane: usize) -> Option<String> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.proxy
+            .send(ProxyMsg::Current { lane, reply: tx })
+            .ok()?;
+
+        match tokio::time::timeout(Self::PROXY_ROTATION_TIMEOUT, rx).await {
+            Ok(Ok(node)) => node,
+            _ => {
+                warn!("Proxy node query timeout");
+                None
+            }
+        }
+    }
+
+    /// 绑定站点凭据存储，恢复该站点在各通道当前节点上的 Cookie 与指纹
+    pub async fn bind_site(&self, site_id: &str) {
+        *self.site.write() = Some(site_id.to_string());
+
+        // 先按站点地区偏好选定出口，再恢复对应节点的凭据
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let msg = ProxyMsg::Bind {
+            site: site_id.to_string(),
+            reply: tx,
+        };
+        if self.proxy.send(msg).is_ok()
+            && tokio::time::timeout(Self::PROXY_ROTATION_TIMEOUT, rx)
+                .await
+                .is_err()
+        {
+            warn!("Proxy site binding timeout");
+        }
+
+        // 各通道分别恢复其出口节点的凭据
+        for (lane, session) in self.sessions.iter().enumerate() {
+            let node = self.lane_node(lane).await;
+            session.attach(CookieLocation::new(&self.config.cache_path, site_id, node));
+        }
+    }
+
+    /// 持久化当前任务所属通道的会话凭据
+    pub fn persist_session(&self) {
+        self.persist_lane_session(self.lane());
     }
 
-    pub fn update_cookies(&self, cookies: &str) {
-        self.session.set_cookie(cookies.to_string());
-        debug!("Session credentials updated");
+    /// 持久化指定通道的会话凭据
+    pub fn persist_lane_session(&self, lane: usize) {
+        match self.lane_session(lane).persist() {
+            Ok(_) => debug!("Session credentials persisted"),
+            Err(e) => warn!("Failed to persist session cookies: {}", e),
+        }
     }
 
     pub async fn bypass_cloudflare(&self, url: &str) -> Result<()> {
@@ -307,4 +424,81 @@ impl ServiceContext {
             true
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::cassette::testing::temp_dir;
+
+    /// 双通道上下文，代理 Actor 以 `node<通道><轮换次数>` 作为节点指纹应答
+    fn lane_context(dir: &std::path::Path) -> ServiceContext {
+        let mut config = AppConfig {
+            cache_path: dir.join("cache").to_string_lossy().into_owned(),
+            ..AppConfig::default()
+        };
+        config.proxy.enabled = true;
+        config.proxy.parallel_nodes = 2;
+        let config = Arc::new(config);
+
+        let (proxy_tx, proxy_rx) = flume::unbounded();
+        tokio::spawn(async move {
+            let mut rotations = [0; 2];
+            while let Ok(msg) = proxy_rx.recv_async().await {
+                match msg {
+                    ProxyMsg::Bind { reply, .. } => {
+                        let _ = reply.send(());
+                    }
+                    ProxyMsg::Current { lane, reply } => {
+                        let _ = reply.send(Some(format!("node{}{}", lane, rotations[lane])));
+                    }
+                    ProxyMsg::Rotate { lane, reply, .. } => {
+                        rotations[lane] += 1;
+                        if let Some(reply) = reply {
+                            let _ = reply.send(());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        ServiceContext::new(
+            Arc::new(HttpService::new(config.clone())),
+            proxy_tx,
+            Arc::new(BrowserService::new(config.clone())),
+            config,
+        )
+    }
+
+    #[tokio::test]
+    async fn each_lane_keeps_credentials_of_its_own_node() {
+        let dir = temp_dir("lanes");
+        let ctx = lane_context(&dir);
+        ctx.bind_site("demo").await;
+
+        egress::scope(1, async {
+            ctx.session().set_account(Some("alice".into()));
+            ctx.persist_session();
+        })
+        .await;
+        assert_eq!(ctx.lane_session(0).account(), None);
+
+        let snapshots = dir.join("cache").join("cookies").join("demo");
+        assert!(snapshots.join("node10.json").exists());
+        assert!(!snapshots.join("node00.json").exists());
+
+        // 轮换通道 1 只切换该通道的凭据
+        ctx.lane_session(0).set_account(Some("bob".into()));
+        egress::scope(1, ctx.force_rotate_proxy(BlockReason::IpBlocked)).await;
+        assert_eq!(ctx.lane_session(1).account(), None);
+        assert_eq!(ctx.lane_session(0).account().as_deref(), Some("bob"));
+
+        // 切回原节点时恢复其凭据
+        ctx.lane_session(1)
+            .switch_node(Some("node10".into()))
+            .unwrap();
+        assert_eq!(ctx.lane_session(1).account().as_deref(), Some("alice"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,234 @@
+//! 会话凭据存储 (Cookie Jar)
+//!
+//! 基于 RFC 6265 的按域 Cookie 存储，遵循 Domain / Path / Expires / Secure / HttpOnly 语义，
+//! 并按站点与代理节点持久化至 `cache/cookies/<site>/<node>.json`。
+
+use std::path::{Path, PathBuf};
+
+use cookie_store::{CookieDomain, CookieExpiration, CookieStore};
+use reqwest::header::{HeaderMap, SET_COOKIE};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use url::Url;
+
+use crate::core::error::Result;
+
+pub use cookie_store::{Cookie as StoredCookie, RawCookie};
+
+/// 直连模式下的节点占位标识
+pub const DIRECT_NODE: &str = "direct";
+
+/// 按域划分的 Cookie 容器
+#[derive(Debug, Default)]
+pub struct CookieJar {
+    store: CookieStore,
+}
+
+impl CookieJar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从快照恢复 (过期项自动丢弃)
+    pub fn from_cookies(cookies: Vec<StoredCookie<'static>>) -> Self {
+        let store = CookieStore::from_cookies(
+            cookies.into_iter().map(Ok::<_, std::convert::Infallible>),
+            false,
+        )
+        .unwrap_or_default();
+        Self { store }
+    }
+
+    /// 生成目标 URL 适用的 `Cookie` 请求头
+    pub fn header_for(&self, url: &Url) -> Option<String> {
+        let header = self
+            .store
+            .get_request_values(url)
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        (!header.is_empty()).then_some(header)
+    }
+
+    /// 捕获响应中的全部 `Set-Cookie` 头部
+    ///
+    /// 返回是否存在 `Set-Cookie`。
+    pub fn store_response(&mut self, url: &Url, headers: &HeaderMap) -> bool {
+        let cookies: Vec<RawCookie<'static>> = headers
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .filter_map(|s| RawCookie::parse(s.to_string()).ok())
+            .collect();
+
+        if cookies.is_empty() {
+            return false;
+        }
+        self.store.store_response_cookies(cookies.into_iter(), url);
+        true
+    }
+
+    /// 写入单个 Cookie (`url` 作为缺省 Domain / Path 的来源)
+    pub fn insert(&mut self, cookie: RawCookie<'static>, url: &Url) -> bool {
+        match self.store.insert_raw(&cookie, url) {
+            Ok(_) => true,
+            Err(e) => {
+                debug!("Rejected cookie {}: {:?}", cookie.name(), e);
+                false
+            }
+        }
+    }
+
+    /// 获取全部未过期的 Cookie
+    pub fn cookies(&self) -> Vec<StoredCookie<'static>> {
+        self.store.iter_unexpired().cloned().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.store.iter_unexpired().next().is_none()
+    }
+
+    pub fn clear(&mut self) {
+        self.store.clear();
+    }
+}
+
+/// 外部来源 Cookie 的通用描述 (浏览器 / 导入文件)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CookieSpec {
+    pub name: String,
+    pub value: String,
+    /// 以 `.` 开头表示域 Cookie，否则为 Host-Only
+    pub domain: String,
+    pub path: String,
+    /// 过期时间 (Unix 秒)，`None` 表示会话 Cookie
+    pub expires: Option<i64>,
+    pub secure: bool,
+    pub http_only: bool,
+}
+
+impl CookieSpec {
+    /// 转换为原始 Cookie 及其归属 URL
+    pub fn into_raw(self) -> Option<(RawCookie<'static>, Url)> {
+        let host = self.domain.trim_start_matches('.');
+        if self.name.is_empty() || host.is_empty() {
+            return None;
+        }
+        let path = if self.path.starts_with('/') {
+            self.path
+        } else {
+            "/".to_string()
+        };
+        let url = Url::parse(&format!("https://{}{}", host, path)).ok()?;
+
+        let mut builder = RawCookie::build((self.name, self.value))
+            .path(path)
+            .secure(self.secure)
+            .http_only(self.http_only);
+        if self.domain.starts_with('.') {
+            builder = builder.domain(host.to_string());
+        }
+        if let Some(ts) = self.expires
+            && let Ok(at) = cookie::time::OffsetDateTime::from_unix_timestamp(ts)
+        {
+            builder = builder.expires(at);
+        }
+
+        Some((builder.build(), url))
+    }
+}
+
+impl CookieSpec {
+    /// 从已存储的 Cookie 还原 (用于导出)
+    pub fn from_stored(cookie: &StoredCookie<'_>) -> Option<Self> {
+        let domain = match &cookie.domain {
+            CookieDomain::HostOnly(host) => host.clone(),
+            CookieDomain::Suffix(suffix) => format!(".{}", suffix),
+            _ => return None,
+        };
+        let expires = match &cookie.expires {
+            CookieExpiration::AtUtc(at) => Some(at.unix_timestamp()),
+            CookieExpiration::SessionEnd => None,
+        };
+
+        Some(Self {
+            name: cookie.name().to_string(),
+            value: cookie.value().to_string(),
+            domain,
+            path: String::from(&cookie.path),
+            expires,
+            secure: cookie.secure().unwrap_or(false),
+            http_only: cookie.http_only().unwrap_or(false),
+        })
+    }
+}
+
+/// 持久化的会话快照
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    /// 获取凭据时使用的指纹 (挑战凭据通常与 UA 绑定)
+    #[serde(default)]
+    pub ua: Option<String>,
+    /// 未过期的 Cookie 集合
+    #[serde(default)]
+    pub cookies: Vec<StoredCookie<'static>>,
+    /// 登录态所属账号 (配置账号变更后需重新登录)
+    #[serde(default)]
+    pub account: Option<String>,
+}
+
+impl SessionSnapshot {
+    pub fn load(path: &Path) -> Option<Self> {
+        let data = std::fs::read_to_string(path).ok()?;
+        match serde_json::from_str(&data) {
+            Ok(snapshot) => Some(snapshot),
+            Err(e) => {
+                debug!(
+                    "Discarding unreadable session snapshot {}: {}",
+                    path.display(),
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// 会话快照的存储位置 (站点目录 + 出口节点)
+#[derive(Debug, Clone)]
+pub struct CookieLocation {
+    /// 站点目录 (`cache/cookies/<site>`)
+    pub dir: PathBuf,
+    /// 出口节点指纹 (`None` 表示直连)
+    pub node: Option<String>,
+}
+
+impl CookieLocation {
+    pub fn new(cache_path: &str, site_id: &str, node: Option<String>) -> Self {
+        Self {
+            dir: PathBuf::from(cache_path).join("cookies").join(site_id),
+            node,
+        }
+    }
+
+    /// 判断外部输入的节点名称能否用作快照文件名 (十六进制指纹或直连占位)
+    pub fn is_valid_node(node: &str) -> bool {
+        node == DIRECT_NODE || (!node.is_empty() && node.bytes().all(|b| b.is_ascii_hexdigit()))
+    }
+
+    /// 快照文件路径
+    pub fn file(&self) -> PathBuf {
+        self.dir.join(format!(
+            "{}.json",
+            self.node.as_deref().unwrap_or(DIRECT_NODE)
+        ))
+    }
+}
@@ -0,0 +1,476 @@
+//! 镜像域名解析器 (Mirror Domain Resolver)
+//!
+//! 针对在编号域名之间轮换的站点，负责探测存活域名、运行时切换基准 URL 并持久化发现结果。
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use futures::future::join_all;
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+use url::Url;
+
+use crate::core::config::MirrorConfig;
+use crate::core::error::{Result, SpiderError};
+use crate::network::context::ServiceContext;
+use crate::network::middleware::SkipPolicy;
+
+/// 编号域名结构 (`<prefix><number><suffix>`，如 `booktoki` + `469` + `.com`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NumberedHost {
+    prefix: String,
+    number: u32,
+    suffix: String,
+}
+
+impl NumberedHost {
+    /// 拆解首个域名标签末尾的数字编号
+    fn parse(host: &str) -> Option<Self> {
+        let (label, rest) = host.split_once('.')?;
+        let prefix = label.trim_end_matches(|c: char| c.is_ascii_digit());
+        let number = label[prefix.len()..].parse().ok()?;
+        (!prefix.is_empty()).then(|| Self {
+            prefix: prefix.to_string(),
+            number,
+            suffix: format!(".{}", rest),
+        })
+    }
+
+    fn with_number(&self, number: u32) -> String {
+        format!("{}{}{}", self.prefix, number, self.suffix)
+    }
+}
+
+/// 持久化的域名发现记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DomainRecord {
+    /// 发现时配置文件中的基准 URL (配置变更后记录失效)
+    configured: String,
+    /// 最近一次确认可用的基准 URL
+    resolved: String,
+}
+
+/// 站点域名解析器
+///
+/// 持有站点当前生效的基准 URL，供索引器、抓取器与策略在运行时共享读取。
+#[derive(Debug)]
+pub struct DomainResolver {
+    /// 站点标识符 (持久化键)
+    site_id: String,
+    /// 配置文件中声明的基准 URL
+    configured: Url,
+    /// 当前生效的基准 URL
+    base: RwLock<Url>,
+    /// 发现策略配置
+    config: MirrorConfig,
+    /// 候选域名页面须包含的站点标识
+    markers: Vec<String>,
+    /// 持久化文件路径
+    state_path: PathBuf,
+}
+
+impl DomainResolver {
+    const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+    const PROBE_MAX_REDIRECTS: usize = 3;
+
+    /// 创建解析器，并优先恢复上次发现的域名
+    ///
+    /// `default_markers` 为站点内置标识，仅在配置未声明 `markers` 时生效。
+    pub fn new(
+        site_id: &str,
+        configured: Url,
+        config: MirrorConfig,
+        default_markers: &[&str],
+        cache_path: &str,
+    ) -> Self {
+        let state_path = PathBuf::from(cache_path).join("domains.json");
+        let markers = if config.markers.is_empty() {
+            default_markers.iter().map(|m| m.to_string()).collect()
+        } else {
+            config.markers.clone()
+        };
+        let resolver = Self {
+            site_id: site_id.to_string(),
+            base: RwLock::new(configured.clone()),
+            configured,
+            config,
+            markers,
+            state_path,
+        };
+
+        if resolver.config.enabled
+            && let Some(url) = resolver.load_record()
+        {
+            info!("Restored mirror domain for {}: {}", resolver.site_id, url);
+            *resolver.base.write().unwrap() = url;
+        }
+
+        resolver
+    }
+
+    /// 获取当前生效的基准 URL
+    pub fn current(&self) -> Url {
+        self.base.read().unwrap().clone()
+    }
+
+    /// 判断主机是否属于本站点的镜像族 (前缀与顶级域均相同，仅编号不同)
+    pub fn is_mirror_host(&self, host: &str) -> bool {
+        let current = self.current();
+        let Some(current_host) = current.host_str() else {
+            return false;
+        };
+        if host == current_host {
+            return true;
+        }
+        match (NumberedHost::parse(current_host), NumberedHost::parse(host)) {
+            (Some(a), Some(b)) => a.prefix == b.prefix && a.suffix == b.suffix,
+            _ => false,
+        }
+    }
+
+    /// 将旧镜像域名下的 URL 改写至当前域名
+    ///
+    /// 确保已缓存的章节链接在域名迁移后依旧可用。
+    pub fn rebase(&self, url: &str) -> String {
+        let current = self.current();
+        let Ok(mut parsed) = Url::parse(url) else {
+            return url.to_string();
+        };
+
+        let stale = match (parsed.host_str(), current.host_str()) {
+            (Some(host), Some(current_host)) => {
+                host != current_host && self.is_mirror_host(host)
+            }
+            _ => false,
+        };
+
+        if stale
+            && parsed.set_scheme(current.scheme()).is_ok()
+            && parsed.set_host(current.host_str()).is_ok()
+            && parsed.set_port(current.port()).is_ok()
+        {
+            return parsed.to_string();
+        }
+
+        url.to_string()
+    }
+
+    /// 采纳重定向目标所指向的新镜像域名
+    ///
+    /// 仅当目标属于同一镜像族、与当前域名不同且首页包含站点标识时生效，返回是否发生切换。
+    pub async fn adopt(&self, ctx: &ServiceContext, target: &str) -> bool {
+        if !self.config.enabled {
+            return false;
+        }
+
+        let Ok(url) = Url::parse(target) else {
+            return false;
+        };
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+
+        if Some(host) == self.current().host_str() || !self.is_mirror_host(host) {
+            return false;
+        }
+
+        let Some(base) = Self::origin_of(&url) else {
+            return false;
+        };
+        match self.verify_candidate(ctx, &base).await {
+            Some(verified) => self.switch_to(verified),
+            None => {
+                warn!("Ignoring redirect to unverified mirror {}", base);
+                false
+            }
+        }
+    }
+
+    /// 切换至已校验的镜像域名并持久化
+    fn switch_to(&self, base: Url) -> bool {
+        if base.host_str() == self.current().host_str() {
+            return false;
+        }
+
+        info!("Mirror domain migrated for {}: {}", self.site_id, base);
+        *self.base.write().unwrap() = base.clone();
+        self.save_record(&base);
+        true
+    }
+
+    /// 执行完整的域名发现流程
+    ///
+    /// 依次校验当前域名、探测后续编号域名、解析落地页，命中后更新并持久化。
+    pub async fn resolve(&self, ctx: &ServiceContext) -> Result<Url> {
+        let current = self.current();
+        if !self.config.enabled {
+            return Ok(current);
+        }
+
+        // 1. 当前域名存活校验 (亦可能被重定向至新镜像)
+        if let Some(alive) = self.check_alive(ctx, &current).await {
+            if alive != current {
+                self.switch_to(alive);
+            }
+            return Ok(self.current());
+        }
+
+        warn!("Domain {} unreachable, probing mirrors...", current);
+
+        // 2. 顺序探测后续编号域名
+        if let Some(found) = self.probe_successors(ctx, &current).await {
+            self.switch_to(found);
+            return Ok(self.current());
+        }
+
+        // 3. 解析落地页
+        if let Some(found) = self.scan_landing_page(ctx).await {
+            self.switch_to(found);
+            return Ok(self.current());
+        }
+
+        Err(SpiderError::Custom(format!(
+            "No reachable mirror found for {} (last known: {})",
+            self.site_id, current
+        )))
+    }
+
+    /// 探测当前域名是否可用，返回其最终基准 URL
+    async fn check_alive(&self, ctx: &ServiceContext, base: &Url) -> Option<Url> {
+        let resp = Self::fetch(ctx, base).await?;
+
+        let status = resp.status();
+        if status.is_redirection() {
+            let target = self.redirect_target(base, &resp)?;
+            return self.verify_candidate(ctx, &Self::origin_of(&target)?).await;
+        }
+
+        // 403/503 通常意味着域名存活但触发了防护，仍视为可用
+        if status == StatusCode::FORBIDDEN || status == StatusCode::SERVICE_UNAVAILABLE {
+            return Some(base.clone());
+        }
+
+        // 停放或被查封的旧域名同样返回 200，须确认页面仍属于本站点
+        (status.is_success() && self.read_marker(base, resp).await).then(|| base.clone())
+    }
+
+    /// 校验候选域名确为本站点，返回其最终基准 URL
+    ///
+    /// 已停放或被查封的旧编号域名同样会返回 200，因此候选域名须返回成功响应且页面包含站点标识。
+    async fn verify_candidate(&self, ctx: &ServiceContext, base: &Url) -> Option<Url> {
+        let mut target = base.clone();
+
+        for _ in 0..=Self::PROBE_MAX_REDIRECTS {
+            let resp = Self::fetch(ctx, &target).await?;
+            let status = resp.status();
+            if status.is_redirection() {
+                target = self.redirect_target(&target, &resp)?;
+                continue;
+            }
+            if !status.is_success() {
+                debug!("Mirror candidate {} responded with {}", target, status);
+                return None;
+            }
+
+            if !self.read_marker(&target, resp).await {
+                return None;
+            }
+            return if target == *base {
+                Some(target)
+            } else {
+                Self::origin_of(&target)
+            };
+        }
+
+        debug!("Mirror candidate {} redirected too many times", base);
+        None
+    }
+
+    /// 发送不经策略链的探测请求
+    async fn fetch(ctx: &ServiceContext, url: &Url) -> Option<reqwest::Response> {
+        let send = ctx
+            .request_builder(Method::GET, url.as_str())
+            .with_extension(SkipPolicy::All)
+            .send();
+
+        match tokio::time::timeout(Self::PROBE_TIMEOUT, send).await {
+            Ok(Ok(resp)) => Some(resp),
+            Ok(Err(e)) => {
+                debug!("Mirror probe failed {}: {}", url, e);
+                None
+            }
+            Err(_) => {
+                debug!("Mirror probe timeout: {}", url);
+                None
+            }
+        }
+    }
+
+    /// 解析指向同族镜像域名的重定向目标
+    fn redirect_target(&self, base: &Url, resp: &reqwest::Response) -> Option<Url> {
+        let location = resp
+            .headers()
+            .get(reqwest::header::LOCATION)?
+            .to_str()
+            .ok()?;
+        let target = base.join(location).ok()?;
+        self.is_mirror_host(target.host_str()?).then_some(target)
+    }
+
+    /// 读取响应页面并判断其是否包含站点标识
+    async fn read_marker(&self, url: &Url, resp: reqwest::Response) -> bool {
+        let body = match tokio::time::timeout(Self::PROBE_TIMEOUT, resp.text()).await {
+            Ok(Ok(body)) => body,
+            _ => return false,
+        };
+        let found = self
+            .markers
+            .iter()
+            .any(|marker| body.contains(marker.as_str()));
+        if !found {
+            debug!("Mirror candidate {} lacks site marker", url);
+        }
+        found
+    }
+
+    /// 并发探测后续 N 个编号域名，返回编号最小的可用域名
+    async fn probe_successors(&self, ctx: &ServiceContext, current: &Url) -> Option<Url> {
+        let numbered = NumberedHost::parse(current.host_str()?)?;
+
+        let candidates: Vec<Url> = (1..=self.config.probe_count)
+            .filter_map(|offset| {
+                let mut url = current.clone();
+                url.set_host(Some(&numbered.with_number(numbered.number + offset)))
+                    .ok()?;
+                Some(url)
+            })
+            .collect();
+
+        debug!(
+            "Probing {} successor domains of {}",
+            candidates.len(),
+            current
+        );
+
+        let results = join_all(
+            candidates
+                .iter()
+                .map(|url| self.verify_candidate(ctx, url)),
+        )
+        .await;
+
+        results.into_iter().flatten().next()
+    }
+
+    /// 从落地页中提取编号最大的同族域名
+    async fn scan_landing_page(&self, ctx: &ServiceContext) -> Option<Url> {
+        let landing = self.config.landing_url.as_deref()?;
+        let current = self.current();
+        let numbered = NumberedHost::parse(current.host_str()?)?;
+
+        let (status, html) = match ctx.probe(landing).await {
+            Ok(r) => r,
+            Err(e) => {
+                warn!("Landing page fetch failed {}: {}", landing, e);
+                return None;
+            }
+        };
+        debug!("Landing page {} responded with {}", landing, status);
+
+        let mut hosts = scan_numbered_hosts(&html, &numbered.prefix);
+        hosts.retain(|host| host.suffix == numbered.suffix);
+        hosts.sort_by_key(|h| std::cmp::Reverse(h.number));
+
+        for host in hosts {
+            let mut url = current.clone();
+            if url.set_host(Some(&host.with_number(host.number))).is_err() {
+                continue;
+            }
+            if let Some(alive) = self.verify_candidate(ctx, &url).await {
+                return Some(alive);
+            }
+        }
+        None
+    }
+
+    /// 规范化为站点根 URL
+    fn origin_of(url: &Url) -> Option<Url> {
+        Url::parse(&url.origin().ascii_serialization()).ok()
+    }
+
+    fn load_records(&self) -> HashMap<String, DomainRecord> {
+        std::fs::read_to_string(&self.state_path)
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default()
+    }
+
+    /// 恢复持久化记录 (仅当配置未变更时有效)
+    fn load_record(&self) -> Option<Url> {
+        let record = self.load_records().remove(&self.site_id)?;
+        (record.configured == self.configured.as_str())
+            .then(|| Url::parse(&record.resolved).ok())
+            .flatten()
+    }
+
+    fn save_record(&self, resolved: &Url) {
+        let mut records = self.load_records();
+        records.insert(
+            self.site_id.clone(),
+            DomainRecord {
+                configured: self.configured.to_string(),
+                resolved: resolved.to_string(),
+            },
+        );
+
+        if let Some(parent) = self.state_path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(&records) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(&self.state_path, json) {
+                    warn!("Failed to persist mirror domain: {}", e);
+                }
+            }
+            Err(e) => warn!("Failed to serialize mirror domain: {}", e),
+        }
+    }
+}
+
+/// 扫描文本中形如 `<prefix><number>.<tld>` 的域名
+fn scan_numbered_hosts(text: &str, prefix: &str) -> Vec<NumberedHost> {
+    let mut hosts = Vec::new();
+
+    for (idx, _) in text.match_indices(prefix) {
+        let rest = &text[idx + prefix.len()..];
+        let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+        if digits.is_empty() {
+            continue;
+        }
+
+        let tail: String = rest[digits.len()..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '.' || *c == '-')
+            .collect();
+        let suffix = tail.trim_end_matches('.');
+        if !suffix.starts_with('.') || suffix.len() < 2 {
+            continue;
+        }
+
+        if let Ok(number) = digits.parse() {
+            let host = NumberedHost {
+                prefix: prefix.to_string(),
+                number,
+                suffix: suffix.to_string(),
+            };
+            if !hosts.contains(&host) {
+                hosts.push(host);
+            }
+        }
+    }
+
+    hosts
+}
@@ -0,0 +1,36 @@
+//! 出口通道 (Egress Lanes)
+//!
+//! `proxy.parallel_nodes` 大于 1 时，代理 Actor 为每条通道启动独立的本地监听端口并绑定不同节点。
+//! 任务执行期间经 task-local 记录所属通道，HTTP 请求与封禁后的轮换均只作用于该通道。
+
+use std::future::Future;
+
+use crate::core::config::AppConfig;
+
+tokio::task_local! {
+    static LANE: usize;
+}
+
+/// 出口通道数 (直连模式下恒为 1)
+pub fn count(config: &AppConfig) -> usize {
+    if config.proxy.enabled {
+        config.proxy.parallel_nodes.max(1)
+    } else {
+        1
+    }
+}
+
+/// 通道对应的本地监听端口
+pub fn port(config: &AppConfig, lane: usize) -> u16 {
+    config.proxy.proxy_port.saturating_add(lane as u16)
+}
+
+/// 当前任务所属通道 (未指定时为主通道 0)
+pub fn current() -> usize {
+    LANE.try_with(|lane| *lane).unwrap_or(0)
+}
+
+/// 在指定通道上执行任务
+pub async fn scope<F: Future>(lane: usize, fut: F) -> F::Output {
+    LANE.scope(lane, fut).await
+}
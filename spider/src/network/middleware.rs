@@ -2,14 +2,20 @@
 //!
 //! 提供请求拦截、会话动态注入及基于策略的反爬检测逻辑。
 
-use reqwest::{Request, Response, StatusCode};
+use reqwest::header::{
+    AUTHORIZATION, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, COOKIE, HOST, HeaderMap,
+    TRANSFER_ENCODING,
+};
+use reqwest::{Method, Request, Response, StatusCode};
 use reqwest_middleware::{Middleware, Next, Result};
 use std::sync::Arc;
-use tracing::warn;
+use tracing::{debug, warn};
+use url::Url;
 
 use crate::core::error::{BlockReason, SpiderError};
 use crate::interfaces::NetworkPolicy;
 use crate::interfaces::policy::PolicyResult;
+use crate::network::RedirectTrail;
 use crate::network::context::ServiceContext;
 
 /// 策略执行控制标记 (Policy Control Flag)
@@ -19,11 +25,25 @@ pub enum SkipPolicy {
     All,
     /// 跳过指定的命名策略 (防止循环重定向)
     One(String),
+    /// 跳过多个命名策略 (如需直接观察 3xx 的修复请求)
+    Many(Vec<String>),
+}
+
+impl SkipPolicy {
+    /// 判断指定策略是否被跳过
+    pub fn skips(&self, name: &str) -> bool {
+        match self {
+            SkipPolicy::All => true,
+            SkipPolicy::One(n) => n == name,
+            SkipPolicy::Many(names) => names.iter().any(|n| n == name),
+        }
+    }
 }
 
 /// 会话凭据注入中间件 (Credential Injection)
 /// 
-/// 在请求分发前，将 `Session` 中的最新指纹 (UA)、凭据 (Cookie) 及自定义头部同步至 `Request`。
+/// 在请求分发前，将 `Session` 中的最新指纹 (UA)、凭据 (Cookie) 及自定义头部同步至 `Request`，
+/// 并在响应返回后将 `Set-Cookie` 写回会话的 Cookie Jar。
 pub struct SessionMiddleware;
 
 #[async_trait::async_trait]
@@ -36,6 +56,7 @@ impl Middleware for SessionMiddleware {
     ) -> Result<Response> {
         if let Some(session) = extensions.get::<std::sync::Arc<crate::network::session::Session>>()
         {
+            let cookie = session.cookie_header(req.url());
             let headers = req.headers_mut();
 
             // 注入动态指纹
@@ -46,9 +67,8 @@ impl Middleware for SessionMiddleware {
                 headers.insert(reqwest::header::USER_AGENT, val);
             }
 
-            // 注入持久凭据
-            if let Some(cookie) = session.get_cookie()
-                && !cookie.is_empty()
+            // 注入当前 URL 适用的凭据
+            if let Some(cookie) = cookie
                 && let Ok(val) = reqwest::header::HeaderValue::from_str(&cookie)
             {
                 headers.insert(reqwest::header::COOKIE, val);
@@ -60,32 +80,40 @@ impl Middleware for SessionMiddleware {
                 headers.insert(k.clone(), v.clone());
             }
         }
-        next.run(req, extensions).await
+
+        let url = req.url().clone();
+        let resp = next.run(req, extensions).await?;
+
+        // 捕获下发的凭据 (Set-Cookie Capture)
+        if let Some(session) = extensions.get::<std::sync::Arc<crate::network::session::Session>>() {
+            session.store_cookies(&url, resp.headers());
+        }
+
+        Ok(resp)
     }
 }
 
+/// 策略链执行结果
+enum Verdict {
+    /// 响应通过全部策略
+    Done(Response),
+    /// 需跟随至下一跳
+    Follow { url: String, status: StatusCode },
+}
+
 /// 反爬拦截与自动化恢复中间件 (Anti-Block Interceptor)
 /// 
 /// 负责响应状态监测并执行挂载的网络策略，当检测到阻断时上报 `RefreshRequired` 以触发外部恢复流水线。
+/// 策略放行的重定向在此逐跳跟随，每一跳均重新经过会话注入与策略校验。
 pub struct AntiBlockMiddleware;
 
-#[async_trait::async_trait]
-impl Middleware for AntiBlockMiddleware {
-    async fn handle(
-        &self,
-        req: Request,
-        extensions: &mut http::Extensions,
-        next: Next<'_>,
-    ) -> Result<Response> {
-        let ctx = extensions
-            .get::<ServiceContext>()
-            .expect("ServiceContext must be present in request extensions")
-            .clone();
-
-        // 执行下游管线请求
-        let resp = next.run(req, extensions).await?;
-
-        // 策略链校验 (Policy Chain Validation)
+impl AntiBlockMiddleware {
+    /// 依次执行策略链 (Policy Chain Validation)
+    async fn apply_policies(
+        resp: Response,
+        ctx: &ServiceContext,
+        extensions: &http::Extensions,
+    ) -> Result<Verdict> {
         let mut current_resp = resp;
         let policies = extensions
             .get::<Vec<Arc<dyn NetworkPolicy>>>()
@@ -95,13 +123,11 @@ impl Middleware for AntiBlockMiddleware {
         let skip_policy = extensions.get::<SkipPolicy>();
 
         for policy in &policies {
-            match skip_policy {
-                Some(SkipPolicy::All) => break,
-                Some(SkipPolicy::One(name)) if name == policy.name() => continue,
-                _ => {}
+            if skip_policy.is_some_and(|s| s.skips(policy.name())) {
+                continue;
             }
 
-            match policy.check(current_resp, &ctx).await {
+            match policy.check(current_resp, ctx).await {
                 Ok(PolicyResult::Pass(r)) => {
                     current_resp = r;
                 }
@@ -113,14 +139,8 @@ impl Middleware for AntiBlockMiddleware {
                         },
                     )));
                 }
-                Ok(PolicyResult::Redirect(url)) => {
-                    warn!("Inbound redirect detected by policy: {}", url);
-                    return Err(reqwest_middleware::Error::from(anyhow::Error::new(
-                        SpiderError::RefreshRequired {
-                            reason: BlockReason::Custom("redirect".into()),
-                            new_url: Some(url),
-                        },
-                    )));
+                Ok(PolicyResult::Redirect { url, status }) => {
+                    return Ok(Verdict::Follow { url, status });
                 }
                 Ok(PolicyResult::Fail(e)) => {
                     return Err(reqwest_middleware::Error::from(anyhow::Error::new(e)));
@@ -129,6 +149,111 @@ impl Middleware for AntiBlockMiddleware {
             }
         }
 
+        Ok(Verdict::Done(current_resp))
+    }
+
+    /// 构造下一跳请求 (Method & Body Semantics)
+    ///
+    /// 303 以及 POST 上的 301/302 降级为无负载 GET；307/308 保留原方法与负载。
+    fn follow_request(
+        method: &Method,
+        headers: &HeaderMap,
+        replay: Option<Request>,
+        status: StatusCode,
+        from: &Url,
+        target: &str,
+    ) -> std::result::Result<Request, SpiderError> {
+        let target = Url::parse(target).map_err(|e| SpiderError::RedirectAborted {
+            url: from.to_string(),
+            reason: format!("invalid target {}: {}", target, e),
+        })?;
+
+        let downgrade = match status {
+            StatusCode::SEE_OTHER => *method != Method::HEAD,
+            StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND => *method == Method::POST,
+            _ => false,
+        };
+
+        let mut req = if downgrade {
+            let mut req = Request::new(Method::GET, target);
+            *req.headers_mut() = headers.clone();
+            *req.timeout_mut() = replay.as_ref().and_then(|r| r.timeout().copied());
+            for name in [
+                CONTENT_TYPE,
+                CONTENT_LENGTH,
+                CONTENT_ENCODING,
+                TRANSFER_ENCODING,
+            ] {
+                req.headers_mut().remove(name);
+            }
+            req
+        } else {
+            let mut req = replay.ok_or_else(|| SpiderError::RedirectAborted {
+                url: from.to_string(),
+                reason: format!("request body cannot be replayed for {}", status),
+            })?;
+            *req.url_mut() = target;
+            req
+        };
+
+        // Cookie 由会话中间件按目标 URL 重新注入；跨源时剥离授权头
+        let headers = req.headers_mut();
+        headers.remove(COOKIE);
+        headers.remove(HOST);
+        if req.url().origin() != from.origin() {
+            req.headers_mut().remove(AUTHORIZATION);
+        }
+
+        Ok(req)
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for AntiBlockMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut http::Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let ctx = extensions
+            .get::<ServiceContext>()
+            .expect("ServiceContext must be present in request extensions")
+            .clone();
+
+        // 探测请求直接透传原始响应 (Raw passthrough for probes)
+        if matches!(extensions.get::<SkipPolicy>(), Some(SkipPolicy::All)) {
+            return next.run(req, extensions).await;
+        }
+
+        let mut req = req;
+        let mut trail: Vec<String> = Vec::new();
+
+        let current_resp = loop {
+            let method = req.method().clone();
+            let url = req.url().clone();
+            let headers = req.headers().clone();
+            let replay = req.try_clone();
+
+            // 执行下游管线请求
+            let mut resp = next.clone().run(req, extensions).await?;
+            resp.extensions_mut().insert(RedirectTrail(trail.clone()));
+
+            match Self::apply_policies(resp, &ctx, extensions).await? {
+                Verdict::Done(resp) => break resp,
+                Verdict::Follow {
+                    url: target,
+                    status,
+                } => {
+                    debug!("Following redirect ({}): {} -> {}", status, url, target);
+
+                    req = Self::follow_request(&method, &headers, replay, status, &url, &target)
+                        .map_err(|e| reqwest_middleware::Error::from(anyhow::Error::new(e)))?;
+                    trail.push(url.to_string());
+                }
+            }
+        };
+
         // 基础设施层级阻断补丁 (Fallback Block Detection)
         let final_status = current_resp.status();
         if final_status == StatusCode::FORBIDDEN || final_status == StatusCode::TOO_MANY_REQUESTS {
@@ -147,3 +272,211 @@ impl Middleware for AntiBlockMiddleware {
         Ok(current_resp)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use parking_lot::Mutex;
+    use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+
+    use super::*;
+    use crate::core::config::{CassetteMode, RedirectConfig};
+    use crate::network::cassette::testing::{replay_context, temp_dir};
+    use crate::network::cassette::{Cassette, CassetteMiddleware, Interaction};
+    use crate::network::policies::RedirectPolicy;
+
+    /// 记录实际发出的每一跳请求 (方法、URL、头部)
+    #[derive(Clone, Default)]
+    struct Sent(Arc<Mutex<Vec<(Method, String, HeaderMap)>>>);
+
+    #[async_trait::async_trait]
+    impl Middleware for Sent {
+        async fn handle(
+            &self,
+            req: Request,
+            extensions: &mut http::Extensions,
+            next: Next<'_>,
+        ) -> Result<Response> {
+            self.0.lock().push((
+                req.method().clone(),
+                req.url().to_string(),
+                req.headers().clone(),
+            ));
+            next.run(req, extensions).await
+        }
+    }
+
+    /// 经录音带回放 3xx 链的反爬中间件客户端
+    struct Harness {
+        ctx: ServiceContext,
+        client: ClientWithMiddleware,
+        sent: Sent,
+    }
+
+    impl Harness {
+        fn new(name: &str, interactions: Vec<Interaction>) -> Self {
+            let dir = temp_dir(name);
+            let recorder = Cassette::new(&dir, CassetteMode::Record);
+            for interaction in interactions {
+                recorder.record(interaction).unwrap();
+            }
+
+            let sent = Sent::default();
+            let cassette = Arc::new(Cassette::new(&dir, CassetteMode::Replay));
+            let client = ClientBuilder::new(reqwest::Client::new())
+                .with(AntiBlockMiddleware)
+                .with(sent.clone())
+                .with(CassetteMiddleware(cassette))
+                .build();
+            Self {
+                ctx: replay_context(&dir),
+                client,
+                sent,
+            }
+        }
+
+        /// 携带 Cookie 与授权头发出请求，仅挂载重定向策略
+        async fn send(
+            &self,
+            method: Method,
+            url: &str,
+            config: RedirectConfig,
+        ) -> Result<Response> {
+            let policies: Vec<Arc<dyn NetworkPolicy>> = vec![Arc::new(RedirectPolicy::new(config))];
+            self.ctx
+                .request_builder_with_client(self.client.clone(), method, url)
+                .header(COOKIE, "sid=1")
+                .header(AUTHORIZATION, "Bearer token")
+                .with_extension(policies)
+                .send()
+                .await
+        }
+
+        fn sent(&self) -> Vec<(Method, String, HeaderMap)> {
+            self.sent.0.lock().clone()
+        }
+    }
+
+    fn redirect(method: &str, from: &str, status: u16, to: &str) -> Interaction {
+        Interaction::new(method, from, status, b"").with_header("location", to)
+    }
+
+    #[tokio::test]
+    async fn aborts_after_hop_limit() {
+        let harness = Harness::new(
+            "redirect-hops",
+            vec![
+                redirect("GET", "https://example.com/1", 302, "/2"),
+                redirect("GET", "https://example.com/2", 302, "/3"),
+                redirect("GET", "https://example.com/3", 302, "/4"),
+            ],
+        );
+        let config = RedirectConfig {
+            max_hops: 2,
+            ..Default::default()
+        };
+
+        let err = harness
+            .send(Method::GET, "https://example.com/1", config)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("exceeded 2 redirect hops"),
+            "{}",
+            err
+        );
+        assert_eq!(harness.sent().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn aborts_on_redirect_loop() {
+        let harness = Harness::new(
+            "redirect-loop",
+            vec![
+                redirect("GET", "https://example.com/a", 302, "/b"),
+                redirect("GET", "https://example.com/b", 302, "/a"),
+            ],
+        );
+
+        let err = harness
+            .send(Method::GET, "https://example.com/a", Default::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("redirect loop"), "{}", err);
+        // 允许一次回访：a -> b -> a -> b，再次跳回 a 时终止
+        assert_eq!(harness.sent().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn downgrades_post_on_303_and_302_but_not_307() {
+        let harness = Harness::new(
+            "redirect-method",
+            vec![
+                redirect("POST", "https://example.com/form", 303, "/done"),
+                Interaction::new("GET", "https://example.com/done", 200, b"ok"),
+                redirect("POST", "https://example.com/legacy", 302, "/done"),
+                redirect("POST", "https://example.com/api", 307, "/api/v2"),
+                Interaction::new("POST", "https://example.com/api/v2", 200, b"ok"),
+            ],
+        );
+
+        for (url, followed) in [
+            ("https://example.com/form", Method::GET),
+            ("https://example.com/legacy", Method::GET),
+            ("https://example.com/api", Method::POST),
+        ] {
+            let resp = harness
+                .send(Method::POST, url, Default::default())
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert_eq!(harness.sent().last().unwrap().0, followed, "{}", url);
+        }
+    }
+
+    #[tokio::test]
+    async fn strips_credentials_on_cross_host_hop() {
+        let harness = Harness::new(
+            "redirect-cross",
+            vec![
+                redirect("GET", "https://example.com/a", 302, "/b"),
+                redirect(
+                    "GET",
+                    "https://example.com/b",
+                    302,
+                    "https://cdn.example.net/c",
+                ),
+                Interaction::new("GET", "https://cdn.example.net/c", 200, b"ok"),
+            ],
+        );
+        let config = RedirectConfig {
+            allowed_hosts: vec!["example.net".into()],
+            ..Default::default()
+        };
+
+        harness
+            .send(Method::GET, "https://example.com/a", config)
+            .await
+            .unwrap();
+
+        let sent = harness.sent();
+        let headers: Vec<_> = sent
+            .iter()
+            .map(|(_, url, h)| {
+                (
+                    url.as_str(),
+                    h.contains_key(COOKIE),
+                    h.contains_key(AUTHORIZATION),
+                )
+            })
+            .collect();
+        // Cookie 由会话中间件按目标重新注入；授权头仅在同源跳转时保留
+        assert_eq!(
+            headers,
+            [
+                ("https://example.com/a", true, true),
+                ("https://example.com/b", false, true),
+                ("https://cdn.example.net/c", false, false),
+            ]
+        );
+    }
+}
@@ -1,10 +1,16 @@
 pub mod browser;
+pub mod captcha;
+pub mod cassette;
 pub mod client;
 pub mod context;
+pub mod cookie;
+pub mod domain;
+pub mod egress;
 pub mod middleware;
 pub mod policies;
 pub mod service;
 pub mod session;
+pub mod snapshot;
 
 use reqwest::Response;
 
@@ -16,6 +22,10 @@ use reqwest::header::HeaderMap;
 
 pub struct OriginalUrl(pub String);
 
+/// 重定向链路扩展，记录抵达当前响应前依次访问过的 URL
+#[derive(Clone, Debug, Default)]
+pub struct RedirectTrail(pub Vec<String>);
+
 /// 响应元数据，用于在 Response 被消费后重建
 pub struct ResponseMetadata {
     pub status: reqwest::StatusCode,
@@ -23,6 +33,7 @@ pub struct ResponseMetadata {
     pub headers: HeaderMap,
     pub url: String,
     pub remote_addr: Option<std::net::SocketAddr>,
+    pub extensions: http::Extensions,
 }
 
 impl ResponseMetadata {
@@ -33,6 +44,7 @@ impl ResponseMetadata {
             headers: resp.headers().clone(),
             url: resp.original_url().to_string(),
             remote_addr: resp.remote_addr(),
+            extensions: resp.extensions().clone(),
         }
     }
 
@@ -48,6 +60,9 @@ impl ResponseMetadata {
         // 复制所有 Headers
         *new_resp.headers_mut() = self.headers;
 
+        // 保留上游扩展 (如重定向链路)
+        *new_resp.extensions_mut() = self.extensions;
+
         // 保留原始 URL 扩展
         new_resp.extensions_mut().insert(OriginalUrl(self.url));
 
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use reqwest::Response;
+use tracing::{info, warn};
+
+use crate::core::config::Credentials;
+use crate::core::error::{BlockReason, Result, SpiderError};
+use crate::interfaces::auth::Authenticator;
+use crate::interfaces::policy::{NetworkPolicy, PolicyResult};
+use crate::network::ResponseMetadata;
+use crate::network::context::ServiceContext;
+
+/// 登录态维护策略
+///
+/// 检测到登录态失效时上报 `TokenExpired`，并在协调器修复锁保护下重新登录。
+#[derive(Debug)]
+pub struct AuthPolicy {
+    /// 站点认证器
+    authenticator: Arc<dyn Authenticator>,
+    /// 账号凭据 (未配置时仅报告失效)
+    credentials: Option<Credentials>,
+}
+
+impl AuthPolicy {
+    pub fn new(authenticator: Arc<dyn Authenticator>, credentials: Option<Credentials>) -> Self {
+        Self {
+            authenticator,
+            credentials,
+        }
+    }
+
+    /// 确保会话已登录
+    ///
+    /// 持久化的登录态属于当前配置账号时直接复用，避免每次运行重复登录。
+    pub async fn ensure_login(&self, ctx: &ServiceContext) -> Result<()> {
+        let Some(credentials) = &self.credentials else {
+            return Ok(());
+        };
+
+        if ctx.session().account().as_deref() == Some(credentials.username.as_str()) {
+            info!("Reusing persisted login for {}", credentials.username);
+            return Ok(());
+        }
+
+        self.login(ctx).await
+    }
+
+    /// 在修复锁保护下执行登录 (Singleton Fixer)
+    ///
+    /// 已有其他任务在登录时挂起等待其完成。
+    pub async fn login(&self, ctx: &ServiceContext) -> Result<()> {
+        let credentials = self.credentials.as_ref().ok_or_else(|| {
+            SpiderError::AuthFailed("login required but no credentials configured".into())
+        })?;
+
+        let Some(_guard) = ctx
+            .coordinator
+            .try_acquire_fix(BlockReason::TokenExpired)
+            .await
+        else {
+            ctx.wait_if_blocked().await;
+            return Ok(());
+        };
+
+        // 仅由持锁任务清除账号标记，避免迟到的失效响应抹掉其他任务刚完成的登录态
+        ctx.session().set_account(None);
+        info!("Logging in as {}...", credentials.username);
+        self.authenticator.login(ctx, credentials).await?;
+        ctx.session().set_account(Some(credentials.username.clone()));
+        ctx.persist_session();
+        info!("Login succeeded");
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl NetworkPolicy for AuthPolicy {
+    fn name(&self) -> &str {
+        "auth"
+    }
+
+    async fn check(&self, resp: Response, ctx: &ServiceContext) -> Result<PolicyResult> {
+        let metadata = ResponseMetadata::from_response(&resp);
+        let bytes = resp.bytes().await.map_err(SpiderError::Network)?;
+        let body = String::from_utf8_lossy(&bytes);
+
+        if !self
+            .authenticator
+            .is_expired(metadata.status, &metadata.headers, &body)
+        {
+            return Ok(PolicyResult::Pass(metadata.rebuild(bytes)));
+        }
+
+        warn!("Session expired at {}", metadata.url);
+
+        match self.login(ctx).await {
+            Ok(_) => Ok(PolicyResult::Retry {
+                is_force: true,
+                reason: BlockReason::TokenExpired,
+            }),
+            Err(e) => Ok(PolicyResult::Fail(e)),
+        }
+    }
+}
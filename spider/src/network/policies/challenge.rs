@@ -0,0 +1,213 @@
+use async_trait::async_trait;
+use reqwest::Response;
+use reqwest::header::{HeaderMap, SET_COOKIE};
+use tracing::{info, warn};
+
+use crate::core::config::{ChallengeConfig, ChallengeReason, ChallengeRule, HeaderMarker};
+use crate::core::error::{BlockReason, Result, SpiderError};
+use crate::interfaces::policy::{NetworkPolicy, PolicyResult};
+use crate::network::context::ServiceContext;
+use crate::network::{ResponseExt, ResponseMetadata};
+
+impl From<ChallengeReason> for BlockReason {
+    fn from(reason: ChallengeReason) -> Self {
+        match reason {
+            ChallengeReason::Challenge => Self::Cloudflare,
+            ChallengeReason::IpBlocked => Self::IpBlocked,
+            ChallengeReason::RateLimit => Self::RateLimit,
+        }
+    }
+}
+
+/// 内置挑战识别规则 (Cloudflare / DDoS-Guard / Sucuri)
+fn builtin_rules() -> Vec<ChallengeRule> {
+    let header = HeaderMarker::new;
+    vec![
+        // Cloudflare 硬封禁 (不可通过浏览器解决)
+        ChallengeRule::builder()
+            .name("cloudflare-block".into())
+            .body(vec![
+                "<title>Attention Required! | Cloudflare</title>".into(),
+                "cf-error-details".into(),
+            ])
+            .reason(ChallengeReason::IpBlocked)
+            .build(),
+        ChallengeRule::builder()
+            .name("cloudflare-mitigated".into())
+            .headers(vec![header("cf-mitigated", Some("challenge"))])
+            .build(),
+        ChallengeRule::builder()
+            .name("cloudflare-interstitial".into())
+            .body(vec![
+                "<title>Just a moment...</title>".into(),
+                "challenges.cloudflare.com/turnstile".into(),
+                "/cdn-cgi/challenge-platform/".into(),
+                "cf-turnstile".into(),
+            ])
+            .build(),
+        ChallengeRule::builder()
+            .name("cloudflare-js-challenge".into())
+            .status(vec![503])
+            .headers(vec![header("server", Some("cloudflare"))])
+            .body(vec!["_cf_chl_opt".into(), "jschl-answer".into()])
+            .build(),
+        ChallengeRule::builder()
+            .name("cloudflare-challenge-cookie".into())
+            .status(vec![403, 503])
+            .cookies(vec!["cf_chl_".into()])
+            .build(),
+        ChallengeRule::builder()
+            .name("ddos-guard".into())
+            .headers(vec![header("server", Some("ddos-guard"))])
+            .body(vec![
+                "check.ddos-guard.net".into(),
+                "ddos-guard/js-challenge".into(),
+                "<title>DDoS-Guard</title>".into(),
+            ])
+            .build(),
+        ChallengeRule::builder()
+            .name("sucuri-block".into())
+            .body(vec!["Sucuri WebSite Firewall - Access Denied".into()])
+            .reason(ChallengeReason::IpBlocked)
+            .build(),
+        ChallengeRule::builder()
+            .name("sucuri-js-challenge".into())
+            .headers(vec![
+                header("x-sucuri-id", None),
+                header("server", Some("sucuri")),
+            ])
+            .body(vec!["sucuri_cloudproxy_js".into()])
+            .build(),
+    ]
+}
+
+/// 反爬挑战检测器 (Challenge Detector)
+///
+/// 依据状态码、响应头、响应体标记及下发的 Cookie 匹配规则表。
+#[derive(Debug)]
+pub struct ChallengeDetector {
+    rules: Vec<ChallengeRule>,
+}
+
+impl Default for ChallengeDetector {
+    fn default() -> Self {
+        Self::new(&ChallengeConfig::default())
+    }
+}
+
+impl ChallengeDetector {
+    /// 合并配置规则与内置规则 (配置优先)
+    pub fn new(config: &ChallengeConfig) -> Self {
+        let mut rules = config.rules.clone();
+        rules.extend(builtin_rules());
+        Self { rules }
+    }
+
+    /// 返回首个命中的规则
+    pub fn detect(&self, status: u16, headers: &HeaderMap, body: &str) -> Option<&ChallengeRule> {
+        self.rules
+            .iter()
+            .find(|rule| Self::matches(rule, status, headers, body))
+    }
+
+    fn matches(rule: &ChallengeRule, status: u16, headers: &HeaderMap, body: &str) -> bool {
+        // 不含任何条件的规则视为无效
+        if rule.status.is_empty()
+            && rule.headers.is_empty()
+            && rule.body.is_empty()
+            && rule.cookies.is_empty()
+        {
+            return false;
+        }
+
+        let status_ok = rule.status.is_empty() || rule.status.contains(&status);
+        let headers_ok = rule.headers.is_empty()
+            || rule
+                .headers
+                .iter()
+                .any(|m| Self::header_matches(m, headers));
+        let body_ok = rule.body.is_empty() || rule.body.iter().any(|m| body.contains(m.as_str()));
+        let cookies_ok = rule.cookies.is_empty()
+            || headers
+                .get_all(SET_COOKIE)
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .any(|c| {
+                    rule.cookies
+                        .iter()
+                        .any(|prefix| c.trim_start().starts_with(prefix.as_str()))
+                });
+
+        status_ok && headers_ok && body_ok && cookies_ok
+    }
+
+    fn header_matches(marker: &HeaderMarker, headers: &HeaderMap) -> bool {
+        let mut values = headers
+            .get_all(marker.name.as_str())
+            .iter()
+            .map(|v| String::from_utf8_lossy(v.as_bytes()).to_ascii_lowercase())
+            .peekable();
+
+        match &marker.value {
+            None => values.peek().is_some(),
+            Some(expected) => {
+                let expected = expected.to_ascii_lowercase();
+                values.any(|v| v.contains(&expected))
+            }
+        }
+    }
+}
+
+/// 反爬挑战检查策略
+///
+/// 可解挑战交由浏览器通过后重试；封禁与限速上报对应 `BlockReason` 以切换节点。
+#[derive(Debug, Default)]
+pub struct ChallengePolicy {
+    detector: ChallengeDetector,
+}
+
+impl ChallengePolicy {
+    pub fn new(config: &ChallengeConfig) -> Self {
+        Self {
+            detector: ChallengeDetector::new(config),
+        }
+    }
+}
+
+#[async_trait]
+impl NetworkPolicy for ChallengePolicy {
+    fn name(&self) -> &str {
+        "challenge"
+    }
+
+    async fn check(&self, resp: Response, ctx: &ServiceContext) -> Result<PolicyResult> {
+        let url_str = resp.original_url().to_string();
+
+        let metadata = ResponseMetadata::from_response(&resp);
+        let bytes = resp.bytes().await.map_err(SpiderError::Network)?;
+        let html = String::from_utf8_lossy(&bytes);
+
+        let Some(rule) = self
+            .detector
+            .detect(metadata.status.as_u16(), &metadata.headers, &html)
+        else {
+            // 重建 Response 继续
+            return Ok(PolicyResult::Pass(metadata.rebuild(bytes)));
+        };
+
+        if rule.reason == ChallengeReason::Challenge {
+            info!("检测到反爬挑战 ({})，正在尝试绕过...", rule.name);
+            ctx.bypass_cloudflare(&url_str).await?;
+        } else {
+            warn!(
+                "检测到访问阻断 ({}, {:?}): {}",
+                rule.name, rule.reason, url_str
+            );
+        }
+
+        Ok(PolicyResult::Retry {
+            is_force: true,
+            reason: rule.reason.into(),
+        })
+    }
+}
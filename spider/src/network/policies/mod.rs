@@ -1,5 +1,7 @@
-pub mod cloudflare;
+pub mod auth;
+pub mod challenge;
 pub mod redirect;
 
-pub use cloudflare::CloudflarePolicy;
+pub use auth::AuthPolicy;
+pub use challenge::{ChallengeDetector, ChallengePolicy};
 pub use redirect::RedirectPolicy;
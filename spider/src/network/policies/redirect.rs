@@ -1,19 +1,68 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use reqwest::Response;
 use tracing::debug;
+use url::Url;
 
-use crate::core::error::Result;
+use crate::core::config::RedirectConfig;
+use crate::core::error::{Result, SpiderError};
 use crate::interfaces::policy::{NetworkPolicy, PolicyResult};
-use crate::network::ResponseExt;
 use crate::network::context::ServiceContext;
+use crate::network::domain::DomainResolver;
+use crate::network::{RedirectTrail, ResponseExt};
 
 /// 重定向处理策略
+///
+/// 校验跳转目标 (跳数上限、循环、跨域白名单)，通过后交由中间件携带会话跟随。
 #[derive(Debug, Default)]
-pub struct RedirectPolicy;
+pub struct RedirectPolicy {
+    /// 跳转与跨域规则
+    config: RedirectConfig,
+    /// 镜像域名解析器 (同族镜像域名视为同站)
+    domain: Option<Arc<DomainResolver>>,
+}
 
 impl RedirectPolicy {
-    pub fn new() -> Self {
-        Self
+    pub fn new(config: RedirectConfig) -> Self {
+        Self {
+            config,
+            domain: None,
+        }
+    }
+
+    /// 绑定镜像域名解析器，允许跟随至同族镜像域名
+    pub fn with_domain(mut self, domain: Arc<DomainResolver>) -> Self {
+        self.domain = Some(domain);
+        self
+    }
+
+    /// 判断跨域目标是否允许跟随
+    fn is_allowed(&self, from: &Url, to: &Url) -> bool {
+        let Some(host) = to.host_str() else {
+            return false;
+        };
+        if from.host_str() == Some(host) {
+            return true;
+        }
+        if let Some(domain) = &self.domain
+            && domain.is_mirror_host(host)
+        {
+            return true;
+        }
+        self.config.allowed_hosts.iter().any(|allowed| {
+            host == allowed
+                || host
+                    .strip_suffix(allowed.as_str())
+                    .is_some_and(|rest| rest.ends_with('.'))
+        })
+    }
+
+    fn abort(url: &str, reason: String) -> PolicyResult {
+        PolicyResult::Fail(SpiderError::RedirectAborted {
+            url: url.to_string(),
+            reason,
+        })
     }
 }
 
@@ -32,19 +81,53 @@ impl NetworkPolicy for RedirectPolicy {
             let location_str = String::from_utf8_lossy(location.as_bytes()).to_string();
             let original_url = resp.original_url();
 
-            let base = url::Url::parse(original_url).map_err(|e| {
-                crate::core::error::SpiderError::Custom(format!("Invalid URL: {}", e))
-            })?;
+            let base = Url::parse(original_url)
+                .map_err(|e| SpiderError::Custom(format!("Invalid URL: {}", e)))?;
 
-            let target_url = base
+            let target = base
                 .join(&location_str)
-                .map_err(|e| {
-                    crate::core::error::SpiderError::Custom(format!("Invalid redirect: {}", e))
-                })?
-                .to_string();
+                .map_err(|e| SpiderError::Custom(format!("Invalid redirect: {}", e)))?;
+
+            let trail = resp
+                .extensions()
+                .get::<RedirectTrail>()
+                .map(|t| t.0.as_slice())
+                .unwrap_or_default();
+
+            // 跳数上限
+            if trail.len() + 1 > self.config.max_hops {
+                return Ok(Self::abort(
+                    original_url,
+                    format!("exceeded {} redirect hops", self.config.max_hops),
+                ));
+            }
+
+            // 循环检测：允许一次回访 (常见于下发 Cookie 后跳回原地址)，再次回访视为循环
+            let visits = trail
+                .iter()
+                .map(String::as_str)
+                .chain(std::iter::once(original_url))
+                .filter(|u| *u == target.as_str())
+                .count();
+            if visits >= 2 {
+                return Ok(Self::abort(
+                    original_url,
+                    format!("redirect loop via {}", target),
+                ));
+            }
+
+            if !self.is_allowed(&base, &target) {
+                return Ok(Self::abort(
+                    original_url,
+                    format!("cross-domain redirect to {} not allowed", target),
+                ));
+            }
 
-            debug!("重定向: {} -> {}", original_url, target_url);
-            return Ok(PolicyResult::Redirect(target_url));
+            debug!("重定向 ({}): {} -> {}", status, original_url, target);
+            return Ok(PolicyResult::Redirect {
+                url: target.to_string(),
+                status,
+            });
         }
 
         Ok(PolicyResult::Pass(resp))
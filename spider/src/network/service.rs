@@ -1,83 +1,99 @@
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
-use reqwest::header::{COOKIE, HeaderMap, HeaderValue, USER_AGENT};
+use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 
 use crate::core::config::AppConfig;
 use crate::core::error::{Result, SpiderError};
 use crate::interfaces::NetworkPolicy;
+use crate::network::cassette::{Cassette, CassetteMiddleware};
 use crate::network::context::ServiceContext;
+use crate::network::egress;
 use crate::network::middleware::{AntiBlockMiddleware, SessionMiddleware, SkipPolicy};
 use crate::network::session::Session;
 
 /// 异步 HTTP 服务层 (Service Layer)
 ///
 /// 封装底层 `reqwest` 客户端，集成中间件管线并管理网络会话状态。
+/// 每条出口通道各持有一个经对应本地端口转发的客户端。
 #[derive(Clone)]
 pub struct HttpService {
-    /// 支持热重载的中间件客户端
-    client: Arc<RwLock<ClientWithMiddleware>>,
-    #[allow(dead_code)]
+    /// 支持热重载的中间件客户端 (按出口通道索引)
+    clients: Arc<Vec<RwLock<ClientWithMiddleware>>>,
     config: Arc<AppConfig>,
-    #[allow(dead_code)]
-    session: Arc<Session>,
+    /// 录制/回放存储 (跨客户端重建保留回放进度)
+    cassette: Option<Arc<Cassette>>,
 }
 
 impl HttpService {
     /// 初始化 HTTP 服务实例
-    pub fn new(config: Arc<AppConfig>, session: Arc<Session>) -> Self {
-        let client = Self::try_build_internal_client(&config, &session)
+    ///
+    /// 初始客户端使用默认会话指纹；轮换后按对应通道的会话重建。
+    pub fn new(config: Arc<AppConfig>) -> Self {
+        let cassette = Cassette::from_config(&config.cassette).map(Arc::new);
+        let session = Session::new();
+        let clients = (0..egress::count(&config))
+            .map(|lane| {
+                Self::try_build_internal_client(&config, &session, cassette.as_ref(), lane)
+                    .map(RwLock::new)
+            })
+            .collect::<Result<Vec<_>>>()
             .expect("CRITICAL: Failed to initialize network client");
         Self {
-            client: Arc::new(RwLock::new(client)),
+            clients: Arc::new(clients),
             config,
-            session,
+            cassette,
         }
     }
 
     /// 客户端热重载 (Hot Swapping)
     ///
-    /// 在代理轮换或连接池失效时重建内部客户端实例。
+    /// 在代理轮换或连接池失效时，按该通道的会话重建指定出口通道的客户端实例。
     /// 利用 Arc 引用计数确保旧客户端在待处理任务完成后平滑释放。
-    pub fn recreate_client(&self) -> Result<()> {
-        let new_client = Self::try_build_internal_client(&self.config, &self.session)?;
-        let mut writer = self.client.write().expect("HttpService lock poisoned");
+    pub fn recreate_client(&self, lane: usize, session: &Session) -> Result<()> {
+        let lane = self.lane_index(lane);
+        let new_client =
+            Self::try_build_internal_client(&self.config, session, self.cassette.as_ref(), lane)?;
+        let mut writer = self.clients[lane]
+            .write()
+            .expect("HttpService lock poisoned");
         *writer = new_client;
         Ok(())
     }
 
+    fn lane_index(&self, lane: usize) -> usize {
+        lane.min(self.clients.len() - 1)
+    }
+
     /// 内部客户端工厂函数 (Client Factory)
     ///
     /// 配置连接池、代理重定向、超时策略以及注入初始会话凭据。
     fn try_build_internal_client(
         config: &AppConfig,
         session: &Session,
+        cassette: Option<&Arc<Cassette>>,
+        lane: usize,
     ) -> Result<ClientWithMiddleware> {
         let use_proxy = config.proxy.enabled;
         let mut client_builder = reqwest::Client::builder();
 
         // 仅在启用代理时配置代理
         if use_proxy {
-            let proxy_url = format!("http://127.0.0.1:{}", config.proxy.proxy_port);
-            client_builder = client_builder
-                .proxy(reqwest::Proxy::all(proxy_url).map_err(SpiderError::Network)?);
+            let proxy_url = format!("http://127.0.0.1:{}", egress::port(config, lane));
+            client_builder =
+                client_builder.proxy(reqwest::Proxy::all(proxy_url).map_err(SpiderError::Network)?);
         }
 
         let mut headers = HeaderMap::new();
 
-        // 基础凭据注入
-        let base_headers = [
-            (USER_AGENT, session.get_ua()),
-            (COOKIE, session.get_cookie().unwrap_or_default()),
-        ];
-
-        headers.extend(
-            base_headers
-                .into_iter()
-                .filter(|(_, v)| !v.is_empty())
-                .filter_map(|(k, v)| HeaderValue::from_str(&v).ok().map(|val| (k, val))),
-        );
+        // 基础指纹注入 (Cookie 由会话中间件按请求 URL 注入)
+        let ua = session.get_ua();
+        if !ua.is_empty()
+            && let Ok(val) = HeaderValue::from_str(&ua)
+        {
+            headers.insert(USER_AGENT, val);
+        }
 
         // 批量同步 Session Headers
         headers.extend(
@@ -97,15 +113,22 @@ impl HttpService {
             .build()
             .map_err(SpiderError::Network)?;
 
-        Ok(ClientBuilder::new(client)
-            .with(SessionMiddleware)
+        // 反爬中间件位于外层，使重定向的每一跳都重新经过会话注入
+        let mut builder = ClientBuilder::new(client)
             .with(AntiBlockMiddleware)
-            .build())
+            .with(SessionMiddleware);
+
+        // 录制/回放位于最内层，直接替代网络收发
+        if let Some(cassette) = cassette {
+            builder = builder.with(CassetteMiddleware(cassette.clone()));
+        }
+
+        Ok(builder.build())
     }
 
-    /// 获取当前活跃的客户端实例 (Thread-safe)
+    /// 获取当前任务所属出口通道的客户端实例 (Thread-safe)
     pub fn client(&self) -> ClientWithMiddleware {
-        self.client
+        self.clients[self.lane_index(egress::current())]
             .read()
             .expect("HttpService lock poisoned")
             .clone()
@@ -137,7 +160,7 @@ impl HttpService {
         let resp = self
             .client()
             .get(url)
-            .with_extension(ctx.session.clone())
+            .with_extension(ctx.session().clone())
             .with_extension(ctx)
             .with_extension(SkipPolicy::All)
             .send()
@@ -1,20 +1,31 @@
 //! 会话状态管理 (Session Management)
-//! 
+//!
 //! 利用内部可变性 (Interior Mutability) 维护跨线程同步的会话凭据、指纹特征及自定义头部。
 
 use reqwest::header::HeaderMap;
 use std::sync::RwLock;
+use tracing::{debug, info};
+use url::Url;
+
+use crate::core::error::Result;
+use crate::network::cookie::{
+    CookieJar, CookieLocation, CookieSpec, RawCookie, SessionSnapshot, StoredCookie,
+};
 
 /// 网络会话容器
-/// 
+///
 /// 存储 Cookie、User-Agent 等动态状态，确保 HTTP 客户端与自动化浏览器之间的身份一致性。
 pub struct Session {
     /// 浏览器指纹 (User-Agent)
     ua: RwLock<String>,
-    /// 身份凭据 (Cookie)
-    cookie: RwLock<Option<String>>,
+    /// 身份凭据 (RFC 6265 Cookie Jar)
+    jar: RwLock<CookieJar>,
     /// 动态注入的自定义头部集
     headers: RwLock<HeaderMap>,
+    /// 凭据持久化位置 (未绑定站点时为空)
+    location: RwLock<Option<CookieLocation>>,
+    /// 当前登录态所属账号
+    account: RwLock<Option<String>>,
 }
 
 impl Session {
@@ -22,8 +33,10 @@ impl Session {
     pub fn new() -> Self {
         Self {
             ua: RwLock::new("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36".to_string()),
-            cookie: RwLock::new(None),
+            jar: RwLock::new(CookieJar::new()),
             headers: RwLock::new(HeaderMap::new()),
+            location: RwLock::new(None),
+            account: RwLock::new(None),
         }
     }
 
@@ -36,13 +49,39 @@ impl Session {
         self.ua.read().unwrap().clone()
     }
 
-    pub fn set_cookie(&self, cookie: String) {
-        let mut guard = self.cookie.write().unwrap();
-        *guard = Some(cookie);
+    /// 生成目标 URL 适用的 `Cookie` 请求头
+    pub fn cookie_header(&self, url: &Url) -> Option<String> {
+        self.jar.read().unwrap().header_for(url)
+    }
+
+    /// 捕获响应下发的 `Set-Cookie`
+    pub fn store_cookies(&self, url: &Url, headers: &HeaderMap) -> bool {
+        self.jar.write().unwrap().store_response(url, headers)
+    }
+
+    /// 写入外部来源 (浏览器、导入文件) 的 Cookie
+    pub fn insert_cookie(&self, cookie: RawCookie<'static>, url: &Url) -> bool {
+        self.jar.write().unwrap().insert(cookie, url)
+    }
+
+    /// 当前登录账号 (未登录时为空)
+    pub fn account(&self) -> Option<String> {
+        self.account.read().unwrap().clone()
+    }
+
+    pub fn set_account(&self, account: Option<String>) {
+        *self.account.write().unwrap() = account;
+    }
+
+    /// 导入外部描述的 Cookie，返回是否被接受
+    pub fn import_cookie(&self, spec: CookieSpec) -> bool {
+        spec.into_raw()
+            .is_some_and(|(cookie, url)| self.insert_cookie(cookie, &url))
     }
 
-    pub fn get_cookie(&self) -> Option<String> {
-        self.cookie.read().unwrap().clone()
+    /// 获取全部未过期的 Cookie
+    pub fn cookies(&self) -> Vec<StoredCookie<'static>> {
+        self.jar.read().unwrap().cookies()
     }
 
     pub fn set_headers(&self, headers: HeaderMap) {
@@ -65,16 +104,65 @@ impl Session {
 
     /// 检查凭据是否有效
     pub fn is_empty(&self) -> bool {
-        self.cookie.read().unwrap().is_none()
+        self.jar.read().unwrap().is_empty()
     }
 
     /// 重置会话状态
     pub fn clear(&self) {
-        let mut cookie = self.cookie.write().unwrap();
-        *cookie = None;
+        self.jar.write().unwrap().clear();
+        self.set_account(None);
         let mut headers = self.headers.write().unwrap();
         headers.clear();
     }
+
+    /// 绑定持久化位置并恢复其中的凭据与指纹
+    pub fn attach(&self, location: CookieLocation) {
+        self.clear();
+
+        let file = location.file();
+        if let Some(snapshot) = SessionSnapshot::load(&file) {
+            let jar = CookieJar::from_cookies(snapshot.cookies);
+            if !jar.is_empty() {
+                info!("Restored session cookies from {}", file.display());
+            }
+            *self.jar.write().unwrap() = jar;
+            if let Some(ua) = snapshot.ua.filter(|ua| !ua.is_empty()) {
+                self.set_ua(ua);
+            }
+            self.set_account(snapshot.account);
+        }
+
+        *self.location.write().unwrap() = Some(location);
+    }
+
+    /// 切换出口节点：保存当前节点凭据后载入新节点的凭据
+    pub fn switch_node(&self, node: Option<String>) -> Result<()> {
+        let current = self.location.read().unwrap().clone();
+        let Some(mut location) = current else {
+            self.clear();
+            return Ok(());
+        };
+
+        self.persist()?;
+        location.node = node;
+        debug!("Switching session storage to {}", location.file().display());
+        self.attach(location);
+        Ok(())
+    }
+
+    /// 将当前凭据写入持久化位置
+    pub fn persist(&self) -> Result<()> {
+        let Some(location) = self.location.read().unwrap().clone() else {
+            return Ok(());
+        };
+
+        let snapshot = SessionSnapshot {
+            ua: Some(self.get_ua()),
+            cookies: self.cookies(),
+            account: self.account(),
+        };
+        snapshot.save(&location.file())
+    }
 }
 
 impl Default for Session {
@@ -0,0 +1,162 @@
+//! 调试快照 (Debug Snapshots)
+//!
+//! 页面解析失败时保存服务端实际返回的内容 (URL、状态码、头部、响应体及失配的选择器)，
+//! 用于区分封禁页、验证码与站点改版。
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use crate::core::config::DebugConfig;
+use crate::core::error::Result;
+use crate::network::client::Page;
+
+/// 快照目录名
+const DEBUG_DIR: &str = "debug";
+/// 元数据文件名
+const META_FILE: &str = "meta.json";
+/// 响应体文件名
+const BODY_FILE: &str = "body.html";
+
+/// 快照元数据
+#[derive(Debug, Serialize)]
+struct SnapshotMeta<'a> {
+    timestamp: u64,
+    url: &'a str,
+    status: u16,
+    headers: Vec<(&'a str, String)>,
+    selector: &'a str,
+    error: &'a str,
+    /// 原始响应体字节数
+    body_bytes: usize,
+    /// 响应体是否被截断
+    truncated: bool,
+}
+
+/// 调试快照存储
+pub struct SnapshotStore {
+    /// 快照根目录 (`<cache_path>/debug`)
+    root: PathBuf,
+    config: DebugConfig,
+}
+
+impl SnapshotStore {
+    pub fn new(cache_path: &str, config: &DebugConfig) -> Self {
+        Self {
+            root: Path::new(cache_path).join(DEBUG_DIR),
+            config: config.clone(),
+        }
+    }
+
+    /// 保存解析失败的页面，返回快照目录
+    ///
+    /// 未启用快照时返回 `None`；写入失败仅记录警告，不掩盖原始解析错误。
+    pub fn save(&self, page: &Page, selector: &str, error: &str) -> Option<PathBuf> {
+        if !self.config.snapshots {
+            return None;
+        }
+
+        match self.write(page, selector, error) {
+            Ok(dir) => {
+                self.prune();
+                Some(dir)
+            }
+            Err(e) => {
+                warn!("Failed to save debug snapshot for {}: {}", page.url, e);
+                None
+            }
+        }
+    }
+
+    fn write(&self, page: &Page, selector: &str, error: &str) -> Result<PathBuf> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+
+        // 同一毫秒内的多次失败追加序号
+        let stem = now.as_millis().to_string();
+        let mut dir = self.root.join(&stem);
+        let mut seq = 1;
+        while dir.exists() {
+            dir = self.root.join(format!("{}-{}", stem, seq));
+            seq += 1;
+        }
+        std::fs::create_dir_all(&dir)?;
+
+        let limit = self.config.max_body_kb.saturating_mul(1024);
+        let body = truncate(&page.text, limit);
+
+        let meta = SnapshotMeta {
+            timestamp: now.as_secs(),
+            url: page.url.as_str(),
+            status: page.status.as_u16(),
+            headers: page
+                .headers
+                .iter()
+                .map(|(k, v)| {
+                    (
+                        k.as_str(),
+                        String::from_utf8_lossy(v.as_bytes()).into_owned(),
+                    )
+                })
+                .collect(),
+            selector,
+            error,
+            body_bytes: page.text.len(),
+            truncated: body.len() < page.text.len(),
+        };
+
+        std::fs::write(dir.join(META_FILE), serde_json::to_vec_pretty(&meta)?)?;
+        std::fs::write(dir.join(BODY_FILE), body)?;
+        debug!("Saved debug snapshot to {}", dir.display());
+        Ok(dir)
+    }
+
+    /// 删除超出保留数量的最旧快照
+    fn prune(&self) {
+        let Ok(entries) = std::fs::read_dir(&self.root) else {
+            return;
+        };
+
+        let mut dirs: Vec<_> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .map(|e| e.path())
+            .collect();
+        if dirs.len() <= self.config.max_snapshots {
+            return;
+        }
+
+        // 目录名为 `<毫秒时间戳>[-序号]`，按其排序即按时间排序
+        dirs.sort_by_key(|p| sort_key(p));
+        let excess = dirs.len() - self.config.max_snapshots;
+        for dir in dirs.into_iter().take(excess) {
+            if let Err(e) = std::fs::remove_dir_all(&dir) {
+                warn!("Failed to remove debug snapshot {}: {}", dir.display(), e);
+            }
+        }
+    }
+}
+
+/// 快照目录排序键 (时间戳, 序号)
+fn sort_key(path: &Path) -> (u128, u32) {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    let (ts, seq) = name.split_once('-').unwrap_or((&name, "0"));
+    (ts.parse().unwrap_or(0), seq.parse().unwrap_or(0))
+}
+
+/// 按字节上限截断 (保持 UTF-8 字符边界)
+fn truncate(text: &str, limit: usize) -> &str {
+    if text.len() <= limit {
+        return text;
+    }
+    let mut end = limit;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
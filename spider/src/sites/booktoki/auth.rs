@@ -0,0 +1,103 @@
+//! Booktoki 账号认证 (Gnuboard Authentication)
+//!
+//! 基于 Gnuboard 表单登录 (`/bbs/login_check.php`)，并识别跳转登录页等登录态失效信号。
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, LOCATION, ORIGIN, REFERER};
+use reqwest::{Method, StatusCode};
+use tracing::debug;
+
+use crate::core::config::Credentials;
+use crate::core::error::{Result, SpiderError};
+use crate::interfaces::auth::Authenticator;
+use crate::network::context::ServiceContext;
+use crate::network::domain::DomainResolver;
+use crate::network::middleware::SkipPolicy;
+
+/// 登录页路径
+const LOGIN_PAGE: &str = "/bbs/login.php";
+/// 会员专属内容的提示文案
+const MEMBERS_ONLY_HINT: &str = "로그인 후 이용";
+
+/// Gnuboard 登录认证器
+#[derive(Debug)]
+pub struct BooktokiAuth {
+    /// 站点域名解析器
+    domain: Arc<DomainResolver>,
+}
+
+impl BooktokiAuth {
+    pub fn new(domain: Arc<DomainResolver>) -> Self {
+        Self { domain }
+    }
+
+    fn normalize(&self, path: &str) -> String {
+        crate::utils::to_absolute_url(&self.domain.current(), path)
+    }
+
+    /// 提取 Gnuboard `alert("...")` 提示文案
+    fn extract_alert(html: &str) -> Option<String> {
+        let start = html.find("alert(")? + "alert(".len();
+        let rest = html[start..].trim_start_matches(['"', '\'']);
+        let end = rest.find(['"', '\''])?;
+        Some(rest[..end].replace("\\n", " "))
+    }
+}
+
+#[async_trait]
+impl Authenticator for BooktokiAuth {
+    fn is_expired(&self, status: StatusCode, headers: &HeaderMap, body: &str) -> bool {
+        if status.is_redirection() {
+            return headers
+                .get(LOCATION)
+                .map(|l| String::from_utf8_lossy(l.as_bytes()).contains(LOGIN_PAGE))
+                .unwrap_or(false);
+        }
+        body.contains(MEMBERS_ONLY_HINT)
+    }
+
+    async fn login(&self, ctx: &ServiceContext, credentials: &Credentials) -> Result<()> {
+        let base = self.domain.current().to_string();
+        let form = [
+            ("url", base.as_str()),
+            ("mb_id", credentials.username.as_str()),
+            ("mb_password", credentials.password.as_str()),
+            ("auto_login", "1"),
+        ];
+
+        // 需直接观察 302 反馈，跳过自身与重定向跟随
+        let resp = ctx
+            .request_builder(Method::POST, &self.normalize("/bbs/login_check.php"))
+            .header(REFERER, self.normalize(LOGIN_PAGE))
+            .header(ORIGIN, base.trim_end_matches('/'))
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(serde_urlencoded::to_string(form).unwrap_or_default())
+            .with_extension(SkipPolicy::Many(vec!["auth".into(), "redirect".into()]))
+            .send()
+            .await
+            .map_err(SpiderError::Middleware)?;
+
+        let status = resp.status();
+        if status.is_redirection() {
+            let location = resp
+                .headers()
+                .get(LOCATION)
+                .map(|l| String::from_utf8_lossy(l.as_bytes()).to_string())
+                .unwrap_or_default();
+            debug!("Login redirected to {}", location);
+
+            if location.contains(LOGIN_PAGE) {
+                return Err(SpiderError::AuthFailed("redirected back to login page".into()));
+            }
+            return Ok(());
+        }
+
+        // 失败时 Gnuboard 以 200 + alert 脚本返回原因
+        let html = resp.text().await.map_err(SpiderError::Network)?;
+        let reason = Self::extract_alert(&html)
+            .unwrap_or_else(|| format!("unexpected response (Status: {})", status));
+        Err(SpiderError::AuthFailed(reason))
+    }
+}
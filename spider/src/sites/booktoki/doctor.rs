@@ -0,0 +1,257 @@
+//! Booktoki 选择器健康检查 (Selector Drift Check)
+//!
+//! 以已知作品依次抓取详情页、目录页与首个章节，统计 `SiteSelectors` 各项命中节点数，
+//! 在站点改版导致全量采集失败之前发现选择器失配。
+
+use std::ops::RangeInclusive;
+
+use scraper::selector::ToCss;
+use scraper::{Html, Selector};
+
+use crate::core::model::{BookItem, Check};
+use crate::interfaces::site::TaskArgs;
+use crate::network::client::{Page, RequestKind};
+
+use super::{Booktoki, SiteSelectors};
+
+/// 至少命中一个节点
+const REQUIRED: RangeInclusive<usize> = 1..=usize::MAX;
+/// 仅报告命中数
+const OPTIONAL: RangeInclusive<usize> = 0..=usize::MAX;
+/// 不应出现
+const ABSENT: RangeInclusive<usize> = 0..=0;
+
+/// 统计选择器命中节点数并按期望判定
+fn selector_check(
+    stage: &str,
+    name: &str,
+    doc: &Html,
+    selector: &Selector,
+    expected: RangeInclusive<usize>,
+) -> Check {
+    let count = doc.select(selector).count();
+    Check::new(
+        stage,
+        name,
+        format!("`{}` matched {} node(s)", selector.to_css_string(), count),
+        expected.contains(&count),
+    )
+}
+
+impl Booktoki {
+    /// 依次检查详情页、目录页与首个章节
+    pub(super) async fn run_diagnosis(&self, args: &TaskArgs) -> Vec<Check> {
+        let mut checks = Vec::new();
+
+        self.check_metadata(args, &mut checks).await;
+        let first = self.check_chapters(args, &mut checks).await;
+
+        match first {
+            Some(url) => self.check_content(&url, &mut checks).await,
+            None => checks.push(Check::new(
+                "content",
+                "fetch",
+                "skipped: no chapter URL available",
+                false,
+            )),
+        }
+
+        checks
+    }
+
+    /// 按类别抓取页面，失败时记录检查项
+    async fn fetch_stage(
+        &self,
+        stage: &str,
+        kind: RequestKind,
+        url: &str,
+        checks: &mut Vec<Check>,
+    ) -> Option<Page> {
+        match self.client.fetch_page(kind, url).await {
+            Ok(page) => {
+                checks.push(Check::new(
+                    stage,
+                    "fetch",
+                    format!("{} (HTTP {})", page.url, page.status.as_u16()),
+                    page.status.is_success(),
+                ));
+                Some(page)
+            }
+            Err(e) => {
+                checks.push(Check::new(stage, "fetch", format!("{}: {}", url, e), false));
+                None
+            }
+        }
+    }
+
+    async fn check_metadata(&self, args: &TaskArgs, checks: &mut Vec<Check>) {
+        const STAGE: &str = "metadata";
+        let s = SiteSelectors::get();
+
+        let url = match self.indexer.build_url("metadata", args) {
+            Ok(url) => url,
+            Err(e) => {
+                checks.push(Check::new(STAGE, "fetch", e.to_string(), false));
+                return;
+            }
+        };
+        let Some(page) = self
+            .fetch_stage(STAGE, RequestKind::Metadata, &url, checks)
+            .await
+        else {
+            return;
+        };
+
+        {
+            let doc = Html::parse_document(&page.text);
+            checks.extend([
+                selector_check(STAGE, "detail_desc", &doc, &s.detail_desc, REQUIRED),
+                // 标题、作者信息与简介各占一个区块
+                selector_check(STAGE, "view_content", &doc, &s.view_content, 3..=usize::MAX),
+                selector_check(STAGE, "bold", &doc, &s.bold, REQUIRED),
+                selector_check(STAGE, "view_img", &doc, &s.view_img, OPTIONAL),
+                selector_check(STAGE, "icon_user", &doc, &s.icon_user, OPTIONAL),
+                selector_check(STAGE, "icon_tags", &doc, &s.icon_tags, OPTIONAL),
+                selector_check(STAGE, "icon_building", &doc, &s.icon_building, OPTIONAL),
+            ]);
+        }
+
+        match self.indexer.parse_metadata_html(&page.text, &page.url) {
+            Ok((metadata, _)) => {
+                checks.push(Check::new(
+                    STAGE,
+                    "title",
+                    metadata.title.clone(),
+                    metadata.title != "Unknown Title",
+                ));
+                checks.push(Check::info(
+                    STAGE,
+                    "author",
+                    metadata.author.unwrap_or_else(|| "(none)".into()),
+                ));
+            }
+            Err(e) => checks.push(Check::new(STAGE, "parse", e.to_string(), false)),
+        }
+    }
+
+    /// 返回首个章节 URL
+    async fn check_chapters(&self, args: &TaskArgs, checks: &mut Vec<Check>) -> Option<String> {
+        const STAGE: &str = "chapters";
+        let s = SiteSelectors::get();
+
+        let url = match self.indexer.build_url("chapters", args) {
+            Ok(url) => url,
+            Err(e) => {
+                checks.push(Check::new(STAGE, "fetch", e.to_string(), false));
+                return None;
+            }
+        };
+        let page = self
+            .fetch_stage(STAGE, RequestKind::ChapterList, &url, checks)
+            .await?;
+
+        {
+            let doc = Html::parse_document(&page.text);
+            checks.extend([
+                selector_check(STAGE, "wr_none", &doc, &s.wr_none, ABSENT),
+                selector_check(STAGE, "list_item", &doc, &s.list_item, REQUIRED),
+                selector_check(STAGE, "wr_subject_link", &doc, &s.wr_subject_link, REQUIRED),
+                selector_check(STAGE, "wr_num", &doc, &s.wr_num, REQUIRED),
+            ]);
+        }
+
+        match self.indexer.parse_chapters_html(&page.text, &page.url) {
+            Ok((items, next)) => {
+                checks.push(Check::new(
+                    STAGE,
+                    "parsed",
+                    format!("{} chapter(s) on first page", items.len()),
+                    !items.is_empty(),
+                ));
+                checks.push(Check::info(
+                    STAGE,
+                    "pagination_next",
+                    match next {
+                        Some(url) => format!("detected: {}", url),
+                        None => "not detected (single page or selector drift)".into(),
+                    },
+                ));
+                items.into_iter().find_map(|item| match item {
+                    BookItem::Chapter(c) if !c.url.is_empty() => Some(c.url),
+                    _ => None,
+                })
+            }
+            Err(e) => {
+                checks.push(Check::new(STAGE, "parsed", e.to_string(), false));
+                None
+            }
+        }
+    }
+
+    async fn check_content(&self, url: &str, checks: &mut Vec<Check>) {
+        const STAGE: &str = "content";
+        let s = SiteSelectors::get();
+
+        let Some(page) = self
+            .fetch_stage(STAGE, RequestKind::Content, url, checks)
+            .await
+        else {
+            return;
+        };
+
+        {
+            let doc = Html::parse_document(&page.text);
+            let paragraphs = doc
+                .select(&s.novel_content)
+                .next()
+                .map(|node| node.select(&s.paragraph).count())
+                .unwrap_or(0);
+
+            checks.push(selector_check(
+                STAGE,
+                "novel_content",
+                &doc,
+                &s.novel_content,
+                REQUIRED,
+            ));
+            checks.push(Check::new(
+                STAGE,
+                "paragraph",
+                format!(
+                    "`{}` matched {} node(s) inside content",
+                    s.paragraph.to_css_string(),
+                    paragraphs
+                ),
+                paragraphs > 0,
+            ));
+            checks.push(selector_check(
+                STAGE,
+                "btn_next",
+                &doc,
+                &s.btn_next,
+                OPTIONAL,
+            ));
+        }
+
+        match self.fetcher.parse_content(&page, &self.client) {
+            Ok((content, _)) => {
+                let text = Html::parse_fragment(&content)
+                    .root_element()
+                    .text()
+                    .collect::<String>();
+                let chars = text.chars().filter(|c| !c.is_whitespace()).count();
+                checks.push(Check::new(
+                    STAGE,
+                    "extracted",
+                    format!(
+                        "{} paragraph(s), {} character(s)",
+                        content.lines().count(),
+                        chars
+                    ),
+                    chars > 0,
+                ));
+            }
+            Err(e) => checks.push(Check::new(STAGE, "extracted", e.to_string(), false)),
+        }
+    }
+}
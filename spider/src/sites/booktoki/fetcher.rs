@@ -4,21 +4,20 @@
 
 use async_trait::async_trait;
 use scraper::{Html, Selector};
-use url::Url;
 
 use super::SiteSelectors;
-use crate::core::error::{Result, SpiderError};
-use crate::network::client::SiteClient;
+use crate::core::error::Result;
+use crate::network::client::{Page, RequestKind, SiteClient};
 
 /// 站点特定正文获取器
-pub struct BooktokiFetcher {
-    /// 站点基准 URL
-    base: Url,
-}
+///
+/// 续页链接以重定向后的最终页面地址为基准解析，无需持有站点基准 URL。
+#[derive(Default)]
+pub struct BooktokiFetcher;
 
 impl BooktokiFetcher {
-    pub fn new(base: Url) -> Self {
-        Self { base }
+    pub fn new() -> Self {
+        Self
     }
 }
 
@@ -29,15 +28,23 @@ impl BooktokiFetcher {
         url: &str,
         client: &SiteClient,
     ) -> Result<(String, Option<String>)> {
-        let html = client.get_text(url).await?;
-        let doc = Html::parse_document(&html);
+        let page = client.fetch_page(RequestKind::Content, url).await?;
+        self.parse_content(&page, client)
+    }
+
+    /// 提取正文并探测续页链接
+    pub(crate) fn parse_content(
+        &self,
+        page: &Page,
+        client: &SiteClient,
+    ) -> Result<(String, Option<String>)> {
+        let doc = Html::parse_document(&page.text);
         let s = SiteSelectors::get();
 
         // Container Positioning
-        let node = doc
-            .select(&s.novel_content)
-            .next()
-            .ok_or_else(|| SpiderError::Parse("Novel content container not found".into()))?;
+        let node = doc.select(&s.novel_content).next().ok_or_else(|| {
+            client.parse_error(page, &s.novel_content, "Novel content container not found")
+        })?;
 
         // Spam Filtering
         let content = node
@@ -78,7 +85,7 @@ impl BooktokiFetcher {
             .select(&s.btn_next)
             .next()
             .and_then(|el| el.value().attr("href"))
-            .map(|href| crate::utils::to_absolute_url(&self.base, href));
+            .map(|href| crate::utils::to_absolute_url(&page.url, href));
 
         Ok((content, next_url))
     }
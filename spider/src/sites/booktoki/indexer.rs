@@ -2,6 +2,8 @@
 //!
 //! 负责站点元数据的抽取与章节层级结构的递归发现。
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use scraper::{ElementRef, Html};
 use url::Url;
@@ -9,26 +11,27 @@ use url::Url;
 use crate::core::error::{Result, SpiderError};
 use crate::core::model::{BookItem, Chapter, Metadata};
 use crate::interfaces::site::TaskArgs;
-use crate::network::client::SiteClient;
+use crate::network::client::{RequestKind, SiteClient};
+use crate::network::domain::DomainResolver;
 use crate::utils::to_absolute_url;
 
 use super::SiteSelectors;
 
 /// 站点特定索引器
 pub struct BooktokiIndexer {
-    /// 站点基准 URL (用于相对路径规范化)
-    base: Url,
+    /// 站点域名解析器 (用于相对路径规范化)
+    domain: Arc<DomainResolver>,
 }
 
 impl BooktokiIndexer {
-    pub fn new(base: Url) -> Self {
-        Self { base }
+    pub fn new(domain: Arc<DomainResolver>) -> Self {
+        Self { domain }
     }
 
     /// 执行相对路径至绝对 URL 的规范化 (URI Normalization)
     #[inline]
     fn normalize(&self, path: &str) -> String {
-        to_absolute_url(&self.base, path)
+        to_absolute_url(&self.domain.current(), path)
     }
 
     /// 基于 CSS 选择器提取紧邻 Icon 节点的文本内容
@@ -47,7 +50,9 @@ impl BooktokiIndexer {
     }
 
     /// 执行元数据 HTML 静态分析 (Metadata Scoping)
-    fn parse_metadata_html(&self, html: &str) -> Result<(Metadata, Option<TaskArgs>)> {
+    ///
+    /// `base` 为页面最终地址，用于解析相对链接。
+    pub(crate) fn parse_metadata_html(&self, html: &str, base: &Url) -> Result<(Metadata, Option<TaskArgs>)> {
         let doc = Html::parse_document(html);
         let s = SiteSelectors::get();
 
@@ -84,7 +89,7 @@ impl BooktokiIndexer {
             .select(&s.view_img)
             .next()
             .and_then(|img| img.value().attr("src"))
-            .map(|url| self.normalize(url));
+            .map(|url| to_absolute_url(base, url));
 
         Ok((
             Metadata {
@@ -101,7 +106,11 @@ impl BooktokiIndexer {
     }
 
     /// 执行章节列表分步解析与分页探测 (Pagination Discovery)
-    fn parse_chapters_html(&self, html: &str) -> Result<(Vec<BookItem>, Option<String>)> {
+    pub(crate) fn parse_chapters_html(
+        &self,
+        html: &str,
+        base: &Url,
+    ) -> Result<(Vec<BookItem>, Option<String>)> {
         if html.is_empty() {
             return Ok((vec![], None));
         }
@@ -140,7 +149,7 @@ impl BooktokiIndexer {
                     index: id.parse().unwrap_or(0),
                     id,
                     title,
-                    url: self.normalize(&url),
+                    url: to_absolute_url(base, &url),
                 }))
             })
             .collect();
@@ -151,13 +160,13 @@ impl BooktokiIndexer {
             .next()
             .and_then(|a| a.value().attr("href"))
             .filter(|href| !href.is_empty() && !href.starts_with('#'))
-            .map(|href| self.normalize(href));
+            .map(|href| to_absolute_url(base, href));
 
         Ok((chapters, next_url))
     }
 
     /// 根据路由变体构建资源定位符
-    fn build_url(&self, kind: &str, args: &TaskArgs) -> Result<String> {
+    pub(crate) fn build_url(&self, kind: &str, args: &TaskArgs) -> Result<String> {
         match kind {
             "metadata" | "chapters" => {
                 let id = args
@@ -178,8 +187,13 @@ impl BooktokiIndexer {
         client: &SiteClient,
     ) -> Result<(Metadata, Option<TaskArgs>)> {
         let url = self.build_url("metadata", args)?;
-        let html = client.get_text(&url).await?;
-        self.parse_metadata_html(&html)
+        let page = client.fetch_page(RequestKind::Metadata, &url).await?;
+        match self.parse_metadata_html(&page.text, &page.url) {
+            Err(SpiderError::Parse(message)) => {
+                Err(client.parse_error(&page, &SiteSelectors::get().detail_desc, &message))
+            }
+            result => result,
+        }
     }
 
     /// 初始化章节列表获取任务
@@ -198,7 +212,7 @@ impl BooktokiIndexer {
         url: &str,
         client: &SiteClient,
     ) -> Result<(Vec<BookItem>, Option<String>)> {
-        let html = client.get_text(url).await?;
-        self.parse_chapters_html(&html)
+        let page = client.fetch_page(RequestKind::ChapterList, url).await?;
+        self.parse_chapters_html(&page.text, &page.url)
     }
 }
@@ -1,9 +1,14 @@
 //! Booktoki 站点模块
 
+mod auth;
+mod doctor;
 mod fetcher;
 mod indexer;
 mod policy;
+mod searcher;
 mod selectors;
+#[cfg(test)]
+mod tests;
 
 use std::sync::Arc;
 
@@ -12,27 +17,35 @@ use url::Url;
 
 use crate::core::config::SiteConfig;
 use crate::core::error::Result;
-use crate::core::model::{BookItem, Metadata};
+use crate::core::model::{BookItem, Check, Metadata, SearchResult};
 use crate::interfaces::site::{Context, TaskArgs};
 use crate::interfaces::{NetworkPolicy, Site, SiteClient};
 use crate::network::context::ServiceContext;
-use crate::network::policies::{CloudflarePolicy, RedirectPolicy};
+use crate::network::domain::DomainResolver;
+use crate::network::policies::{AuthPolicy, ChallengePolicy, RedirectPolicy};
 
+pub use self::auth::BooktokiAuth;
 pub use self::fetcher::BooktokiFetcher;
 pub use self::indexer::BooktokiIndexer;
-pub use self::policy::CaptchaPolicy;
+pub use self::policy::{BooktokiOcr, CaptchaPolicy};
+pub use self::searcher::BooktokiSearcher;
 pub use self::selectors::SiteSelectors;
 
 /// Booktoki 站点实现
 pub struct Booktoki {
     config: SiteConfig,
-    base: Url,
+    domain: Arc<DomainResolver>,
     indexer: BooktokiIndexer,
     fetcher: BooktokiFetcher,
+    searcher: BooktokiSearcher,
+    auth: Arc<AuthPolicy>,
     client: SiteClient,
 }
 
 impl Booktoki {
+    /// 镜像候选域名须包含的站点标识
+    const SITE_MARKERS: &'static [&'static str] = &["북토끼"];
+
     /// 创建新的 Booktoki 站点实例
     pub fn new(config: SiteConfig, ctx: ServiceContext) -> Self {
         let base_url = config
@@ -40,21 +53,40 @@ impl Booktoki {
             .as_deref()
             .unwrap_or("https://booktoki469.com");
         let base = Url::parse(base_url).expect("Invalid base URL");
+        let domain = Arc::new(DomainResolver::new(
+            "booktoki",
+            base,
+            config.mirror.clone(),
+            Self::SITE_MARKERS,
+            &ctx.config.cache_path,
+        ));
+
+        let auth = Arc::new(AuthPolicy::new(
+            Arc::new(BooktokiAuth::new(domain.clone())),
+            config.credentials("booktoki"),
+        ));
 
         // 初始化策略链
         let policies: Vec<Arc<dyn NetworkPolicy>> = vec![
-            Arc::new(CaptchaPolicy::new(base.clone())),
-            Arc::new(CloudflarePolicy::new()),
-            Arc::new(RedirectPolicy::new()),
+            Arc::new(CaptchaPolicy::new(
+                domain.clone(),
+                &config.captcha,
+                &ctx.config.cache_path,
+            )),
+            Arc::new(ChallengePolicy::new(&config.challenge)),
+            auth.clone(),
+            Arc::new(RedirectPolicy::new(config.redirect.clone()).with_domain(domain.clone())),
         ];
 
-        let client = SiteClient::new(ctx, policies);
+        let client = SiteClient::new(ctx, policies).with_domain(domain.clone());
 
         Self {
-            indexer: BooktokiIndexer::new(base.clone()),
-            fetcher: BooktokiFetcher::new(base.clone()),
+            indexer: BooktokiIndexer::new(domain.clone()),
+            fetcher: BooktokiFetcher::new(),
+            searcher: BooktokiSearcher::new(domain.clone()),
+            auth,
             client,
-            base,
+            domain,
             config,
         }
     }
@@ -62,7 +94,7 @@ impl Booktoki {
     /// 规范化 URL
     #[inline]
     pub fn normalize(&self, path: &str) -> String {
-        crate::utils::to_absolute_url(&self.base, path)
+        crate::utils::to_absolute_url(&self.domain.current(), path)
     }
 }
 
@@ -76,8 +108,8 @@ impl Site for Booktoki {
         &self.config
     }
 
-    fn base_url(&self) -> &str {
-        self.base.as_str()
+    fn base_url(&self) -> String {
+        self.domain.current().to_string()
     }
 
     fn client(&self) -> &SiteClient {
@@ -127,8 +159,22 @@ impl Site for Booktoki {
         Ok(full_text)
     }
 
-    async fn prepare(&self, _ctx: &Context) -> Result<()> {
+    async fn search(&self, _ctx: &Context, query: &str, page: u32) -> Result<Vec<SearchResult>> {
+        self.searcher.search(query, page, self.client()).await
+    }
+
+    async fn diagnose(&self, ctx: &Context) -> Result<Vec<Check>> {
+        Ok(self.run_diagnosis(&ctx.args).await)
+    }
+
+    async fn login(&self, ctx: &Context) -> Result<()> {
+        self.auth.login(&ctx.core).await
+    }
+
+    async fn prepare(&self, ctx: &Context) -> Result<()> {
         tracing::info!("Booktoki 正在预热...");
-        Ok(())
+        let base = self.domain.resolve(&ctx.core).await?;
+        tracing::info!("Booktoki 当前域名: {}", base);
+        self.auth.ensure_login(&ctx.core).await
     }
 }
@@ -2,120 +2,219 @@
 //!
 //! 实现自动化 K-CAPTCHA 识别与会话修复逻辑。
 
+use std::sync::Arc;
+use std::time::Duration;
+
 use async_trait::async_trait;
 use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
 use reqwest::{Method, Response};
 use serde_json::json;
+use tokio::time::timeout;
 use tracing::{info, warn};
-use url::Url;
 
+use crate::core::config::CaptchaConfig;
 use crate::core::error::{BlockReason, Result, SpiderError};
+use crate::interfaces::captcha::{CaptchaSolution, CaptchaSolver};
 use crate::interfaces::policy::{NetworkPolicy, PolicyResult};
 use crate::network::ResponseExt;
+use crate::network::captcha::{SampleOutcome, SampleRecorder, SolveAttempt, SolverChain};
 use crate::network::context::ServiceContext;
+use crate::network::domain::DomainResolver;
 use crate::network::middleware::SkipPolicy;
 
+/// 修复请求需直接观察 3xx 反馈，因此同时跳过自身与重定向跟随
+fn raw_skip() -> SkipPolicy {
+    SkipPolicy::Many(vec!["booktoki_captcha".into(), "redirect".into()])
+}
+
+/// 单个网络步骤的超时上限
+const STEP_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// K-CAPTCHA 验证码长度范围
+const KCAPTCHA_LEN: std::ops::RangeInclusive<usize> = 4..=6;
+
+/// 本地 K-CAPTCHA OCR 识别器
+///
+/// OCR 本身不输出置信度，以结果是否符合 K-CAPTCHA 字符集与长度估算。
+#[derive(Debug, Default)]
+pub struct BooktokiOcr;
+
+#[async_trait]
+impl CaptchaSolver for BooktokiOcr {
+    fn name(&self) -> &str {
+        "ocr"
+    }
+
+    async fn solve(&self, image: &[u8]) -> Result<CaptchaSolution> {
+        let bytes = image.to_vec();
+        // 计算密集型任务移交阻塞线程池 (Offloading to Blocking Thread)
+        let code = tokio::task::spawn_blocking(move || booktoki_captcha::solve_captcha(&bytes))
+            .await
+            .map_err(|_| SpiderError::Custom("OCR worker panicked".into()))?
+            .map_err(|_| SpiderError::CaptchaFailed)?;
+
+        let well_formed =
+            KCAPTCHA_LEN.contains(&code.len()) && code.chars().all(|c| c.is_ascii_digit());
+        Ok(CaptchaSolution {
+            confidence: if well_formed { 0.9 } else { 0.3 },
+            code,
+        })
+    }
+}
+
 /// 自动化验证码处理器
 #[derive(Debug)]
 pub struct CaptchaPolicy {
-    /// 站点基准 URL
-    base: Url,
+    /// 站点域名解析器
+    domain: Arc<DomainResolver>,
+    /// 识别器链
+    solvers: SolverChain,
+    /// 同一节点上的最大尝试次数
+    max_attempts: u32,
+    /// 训练样本记录器 (可选)
+    recorder: Option<SampleRecorder>,
 }
 
 impl CaptchaPolicy {
-    pub fn new(base: Url) -> Self {
-        Self { base }
+    pub fn new(domain: Arc<DomainResolver>, config: &CaptchaConfig, cache_path: &str) -> Self {
+        Self {
+            domain,
+            solvers: SolverChain::from_config(config, Some(Arc::new(BooktokiOcr)), cache_path),
+            max_attempts: config.max_attempts.max(1),
+            recorder: config
+                .record_samples
+                .then(|| SampleRecorder::new(cache_path, "booktoki")),
+        }
     }
 
     fn normalize(&self, path: &str) -> String {
-        crate::utils::to_absolute_url(&self.base, path)
+        crate::utils::to_absolute_url(&self.domain.current(), path)
     }
 
     /// 执行验证码识别与会话修复流水线 (Challenge-Response Pipeline)
+    ///
+    /// 识别置信度不足或提交被拒时在当前节点刷新验证码重试，耗尽次数后由调用方切换节点。
     async fn do_solve_captcha(&self, url: &str, ctx: &ServiceContext) -> Result<()> {
-        let solve_inner = async {
-            // 1. 初始化验证码会话 (Session Initialization)
-            let session_url = self.normalize("/plugin/kcaptcha/kcaptcha_session.php");
-            ctx.request_builder(Method::POST, &session_url)
-                .header(reqwest::header::CONTENT_TYPE, "application/json")
-                .body(serde_json::to_vec(&json!({})).unwrap_or_default())
-                .with_extension(SkipPolicy::One("booktoki_captcha".into())) // 避免递归拦截 (Recursion Prevention)
-                .send()
-                .await
-                .map_err(SpiderError::Middleware)?;
-
-            // 2. 采集验证码特征素材
-            let ts = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_millis();
-            let img_url = self.normalize(&format!("/plugin/kcaptcha/kcaptcha_image.php?t={}", ts));
-
-            info!("Fetching CAPTCHA artifact...");
-            let res = ctx
-                .request_builder(Method::GET, &img_url)
-                .with_extension(SkipPolicy::One("booktoki_captcha".into()))
-                .send()
+        for attempt in 1..=self.max_attempts {
+            info!("Solving CAPTCHA ({}/{})...", attempt, self.max_attempts);
+
+            let image = timeout(STEP_TIMEOUT, self.fetch_image(ctx))
                 .await
-                .map_err(SpiderError::Middleware)?;
+                .map_err(|_| SpiderError::Custom("CAPTCHA fetch timeout".into()))??;
 
-            let bytes = res.bytes().await.map_err(SpiderError::Network)?;
+            let solved = self.solvers.solve(&image).await;
+            let Some(chosen) = solved.chosen() else {
+                warn!("No solver produced a confident answer, refreshing CAPTCHA...");
+                self.record_sample(&image, &solved, SampleOutcome::Unsolved)
+                    .await;
+                continue;
+            };
+            info!(
+                "CAPTCHA identified by {} ({:.2}): {}, submitting payload...",
+                chosen.solver, chosen.solution.confidence, chosen.solution.code
+            );
 
-            // 3. 执行 OCR 识别 (Offloading to Blocking Thread)
-            let code = tokio::task::spawn_blocking(move || booktoki_captcha::solve_captcha(&bytes))
-                .await
-                .map_err(|_| SpiderError::Custom("OCR worker panicked".into()))?
-                .map_err(|_| SpiderError::CaptchaFailed)?;
-
-            info!("OCR identification successful: {}, submitting payload...", code);
-
-            // 4. 提交凭据校验 (Credential Submission)
-            let encoded_url = utf8_percent_encode(url, NON_ALPHANUMERIC).to_string();
-            let captcha_page_url = self.normalize(&format!("/bbs/captcha.php?url={}", encoded_url));
-            let submit_url = self.normalize("/bbs/captcha_check.php");
-            let form = vec![
-                ("url".to_string(), url.to_string()),
-                ("captcha_key".to_string(), code),
-            ];
-
-            let submit_res = ctx
-                .request_builder(Method::POST, &submit_url)
-                .header(reqwest::header::REFERER, captcha_page_url)
-                .header(reqwest::header::ORIGIN, self.base.to_string())
-                .header(
-                    reqwest::header::CONTENT_TYPE,
-                    "application/x-www-form-urlencoded",
-                )
-                .body(serde_urlencoded::to_string(&form).unwrap_or_default())
-                .with_extension(SkipPolicy::One("booktoki_captcha".into()))
-                .send()
+            let code = chosen.solution.code.clone();
+            let accepted = timeout(STEP_TIMEOUT, self.submit(url, code, ctx))
                 .await
-                .map_err(SpiderError::Middleware)?;
-
-            // 5. 校验跳转反馈 (Redirect Feedback Analysis)
-            let status = submit_res.status();
-            if status.is_redirection() {
-                let location = submit_res
-                    .headers()
-                    .get(reqwest::header::LOCATION)
-                    .map(|l: &reqwest::header::HeaderValue| String::from_utf8_lossy(l.as_bytes()))
-                    .unwrap_or_default();
-
-                if location.contains("captcha.php") {
-                    return Err(SpiderError::CaptchaFailed);
-                }
-                info!("CAPTCHA verification passed");
+                .map_err(|_| SpiderError::Custom("CAPTCHA submission timeout".into()))??;
+            self.solvers.record(&chosen.solver, accepted);
+
+            let outcome = if accepted {
+                SampleOutcome::Accepted
             } else {
-                warn!("Submission unexpected response (Status: {})", status);
-                return Err(SpiderError::CaptchaFailed);
+                SampleOutcome::Rejected
+            };
+            self.record_sample(&image, &solved, outcome).await;
+
+            if accepted {
+                info!("CAPTCHA verification passed");
+                return Ok(());
             }
+            warn!("CAPTCHA rejected, retrying on current node...");
+        }
+
+        Err(SpiderError::CaptchaFailed)
+    }
+
+    /// 保存训练样本 (未启用时忽略，失败不影响修复流程)
+    async fn record_sample(&self, image: &[u8], solved: &SolveAttempt, outcome: SampleOutcome) {
+        if let Some(recorder) = &self.recorder
+            && let Err(e) = recorder.record(image, solved, outcome).await
+        {
+            warn!("Failed to record CAPTCHA sample: {}", e);
+        }
+    }
 
-            Ok::<(), SpiderError>(())
-        };
+    /// 初始化验证码会话并采集图像素材
+    async fn fetch_image(&self, ctx: &ServiceContext) -> Result<Vec<u8>> {
+        // 1. 初始化验证码会话 (Session Initialization)
+        let session_url = self.normalize("/plugin/kcaptcha/kcaptcha_session.php");
+        ctx.request_builder(Method::POST, &session_url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(&json!({})).unwrap_or_default())
+            .with_extension(raw_skip()) // 避免递归拦截 (Recursion Prevention)
+            .send()
+            .await
+            .map_err(SpiderError::Middleware)?;
+
+        // 2. 采集验证码特征素材
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let img_url = self.normalize(&format!("/plugin/kcaptcha/kcaptcha_image.php?t={}", ts));
+
+        info!("Fetching CAPTCHA artifact...");
+        let res = ctx
+            .request_builder(Method::GET, &img_url)
+            .with_extension(raw_skip())
+            .send()
+            .await
+            .map_err(SpiderError::Middleware)?;
+
+        let bytes = res.bytes().await.map_err(SpiderError::Network)?;
+        Ok(bytes.to_vec())
+    }
+
+    /// 提交凭据并校验跳转反馈，返回是否通过
+    async fn submit(&self, url: &str, code: String, ctx: &ServiceContext) -> Result<bool> {
+        // 3. 提交凭据校验 (Credential Submission)
+        let encoded_url = utf8_percent_encode(url, NON_ALPHANUMERIC).to_string();
+        let captcha_page_url = self.normalize(&format!("/bbs/captcha.php?url={}", encoded_url));
+        let submit_url = self.normalize("/bbs/captcha_check.php");
+        let form = vec![
+            ("url".to_string(), url.to_string()),
+            ("captcha_key".to_string(), code),
+        ];
 
-        tokio::time::timeout(std::time::Duration::from_secs(45), solve_inner)
+        let submit_res = ctx
+            .request_builder(Method::POST, &submit_url)
+            .header(reqwest::header::REFERER, captcha_page_url)
+            .header(reqwest::header::ORIGIN, self.domain.current().to_string())
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                "application/x-www-form-urlencoded",
+            )
+            .body(serde_urlencoded::to_string(&form).unwrap_or_default())
+            .with_extension(raw_skip())
+            .send()
             .await
-            .map_err(|_| SpiderError::Custom("CAPTCHA resolution timeout".into()))?
+            .map_err(SpiderError::Middleware)?;
+
+        // 4. 校验跳转反馈 (Redirect Feedback Analysis)
+        let status = submit_res.status();
+        if !status.is_redirection() {
+            warn!("Submission unexpected response (Status: {})", status);
+            return Ok(false);
+        }
+
+        let location = submit_res
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .map(|l| String::from_utf8_lossy(l.as_bytes()).to_string())
+            .unwrap_or_default();
+        Ok(!location.contains("captcha.php"))
     }
 
     /// 执行抢占式修复任务
@@ -138,9 +237,10 @@ impl CaptchaPolicy {
                     })
                 }
                 Err(e) => {
-                    // 修复失败，强行执行节点轮换。
+                    // 当前节点重试耗尽，强行执行节点轮换。
                     // 注意：此处必须使用非阻塞锁版本的切换，防止死锁。
-                    ctx.force_rotate_proxy().await;
+                    ctx.force_rotate_proxy(BlockReason::Custom("captcha".into()))
+                        .await;
                     Err(e)
                 }
             }
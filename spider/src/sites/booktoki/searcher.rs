@@ -0,0 +1,117 @@
+//! Booktoki 检索器 (Catalog Searcher)
+//!
+//! 负责站内检索结果页的解析，将列表条目映射为结构化的 `SearchResult`。
+
+use std::sync::Arc;
+
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+use scraper::{ElementRef, Html, Selector};
+use url::Url;
+
+use crate::core::error::Result;
+use crate::core::model::SearchResult;
+use crate::network::client::{RequestKind, SiteClient};
+use crate::network::domain::DomainResolver;
+use crate::utils::to_absolute_url;
+
+use super::SiteSelectors;
+
+/// 站点特定检索器
+pub struct BooktokiSearcher {
+    /// 站点域名解析器
+    domain: Arc<DomainResolver>,
+}
+
+impl BooktokiSearcher {
+    pub fn new(domain: Arc<DomainResolver>) -> Self {
+        Self { domain }
+    }
+
+    /// 构建检索结果页 URL
+    fn build_url(&self, query: &str, page: u32) -> String {
+        let encoded = utf8_percent_encode(query, NON_ALPHANUMERIC);
+        to_absolute_url(
+            &self.domain.current(),
+            &format!("/novel?stx={}&page={}", encoded, page.max(1)),
+        )
+    }
+
+    /// 从作品链接中提取资源 ID (`/novel/<id>`)
+    fn extract_id(href: &str) -> Option<String> {
+        let path = href.split(['?', '#']).next()?;
+        let mut segments = path.trim_end_matches('/').rsplit('/');
+        let id = segments.next()?;
+        (segments.next() == Some("novel") && !id.is_empty()).then(|| id.to_string())
+    }
+
+    /// 基于 CSS 选择器提取紧邻 Icon 节点的文本内容
+    fn extract_icon_text(item: &ElementRef, selector: &Selector) -> Option<String> {
+        item.select(selector)
+            .next()
+            .and_then(|el| el.next_sibling())
+            .and_then(|n| n.value().as_text())
+            .map(|t| t.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+
+    /// 执行检索结果 HTML 静态分析
+    pub(crate) fn parse_search_html(&self, html: &str, base: &Url) -> Vec<SearchResult> {
+        let doc = Html::parse_document(html);
+        let s = SiteSelectors::get();
+
+        doc.select(&s.search_item)
+            .filter_map(|item| {
+                let href = item.select(&s.search_link).next()?.value().attr("href")?;
+                let id = Self::extract_id(href)?;
+
+                let title = item
+                    .select(&s.search_title)
+                    .next()
+                    .map(|el| el.text().collect::<String>().trim().to_string())
+                    .filter(|t| !t.is_empty())
+                    .unwrap_or_else(|| "Unknown Title".into());
+
+                let tags = Self::extract_icon_text(&item, &s.icon_tags)
+                    .map(|t| t.split(',').map(|s| s.trim().to_string()).collect())
+                    .unwrap_or_default();
+
+                let chapter_count = Self::extract_icon_text(&item, &s.icon_list).and_then(|t| {
+                    t.chars()
+                        .filter(char::is_ascii_digit)
+                        .collect::<String>()
+                        .parse()
+                        .ok()
+                });
+
+                let cover_url = item
+                    .select(&s.search_img)
+                    .next()
+                    .and_then(|img| img.value().attr("src"))
+                    .map(|src| to_absolute_url(base, src));
+
+                Some(SearchResult {
+                    id,
+                    title,
+                    author: Self::extract_icon_text(&item, &s.icon_user),
+                    tags,
+                    cover_url,
+                    chapter_count,
+                })
+            })
+            .collect()
+    }
+}
+
+impl BooktokiSearcher {
+    /// 执行检索请求与结果解析
+    pub async fn search(
+        &self,
+        query: &str,
+        page: u32,
+        client: &SiteClient,
+    ) -> Result<Vec<SearchResult>> {
+        let url = self.build_url(query, page);
+        let page = client.fetch_page(RequestKind::Search, &url).await?;
+        Ok(self.parse_search_html(&page.text, &page.url))
+    }
+}
@@ -23,6 +23,11 @@ pub struct SiteSelectors {
     pub btn_next: Selector,
     pub novel_content: Selector,
     pub paragraph: Selector,
+    pub search_item: Selector,
+    pub search_link: Selector,
+    pub search_img: Selector,
+    pub search_title: Selector,
+    pub icon_list: Selector,
 }
 
 static SELECTORS: OnceLock<SiteSelectors> = OnceLock::new();
@@ -46,6 +51,11 @@ impl SiteSelectors {
             btn_next: Selector::parse("a.btn-next").unwrap(),
             novel_content: Selector::parse("div#novel_content").unwrap(),
             paragraph: Selector::parse("p").unwrap(),
+            search_item: Selector::parse("ul#webtoon-list-all > li").unwrap(),
+            search_link: Selector::parse("div.img-item a[href]").unwrap(),
+            search_img: Selector::parse("div.img-item img").unwrap(),
+            search_title: Selector::parse("span.title").unwrap(),
+            icon_list: Selector::parse("i.fa-list").unwrap(),
         })
     }
 }
@@ -0,0 +1,417 @@
+//! Booktoki 离线测试 (Fixture & Replay Tests)
+
+use std::sync::Arc;
+
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, HeaderValue, LOCATION};
+use url::Url;
+
+use crate::core::config::{CassetteMode, SiteConfig};
+use crate::core::error::BlockReason;
+use crate::core::model::BookItem;
+use crate::interfaces::NetworkPolicy;
+use crate::interfaces::auth::Authenticator;
+use crate::interfaces::policy::PolicyResult;
+use crate::network::cassette::testing::{replay_context, temp_dir};
+use crate::network::cassette::{Cassette, Interaction};
+use crate::network::domain::DomainResolver;
+use crate::network::policies::ChallengePolicy;
+
+use super::*;
+
+const BASE: &str = "https://booktoki469.com";
+
+const CHAPTERS: &str = include_str!("../../../tests/fixtures/booktoki/chapters.html");
+const CHAPTERS_EMPTY: &str = include_str!("../../../tests/fixtures/booktoki/chapters_empty.html");
+const NOVEL: &str = include_str!("../../../tests/fixtures/booktoki/novel.html");
+const CONTENT: &str = include_str!("../../../tests/fixtures/booktoki/content.html");
+const SEARCH: &str = include_str!("../../../tests/fixtures/booktoki/search.html");
+const SEARCH_EMPTY: &str = include_str!("../../../tests/fixtures/booktoki/search_empty.html");
+const ATTENTION_REQUIRED: &str =
+    include_str!("../../../tests/fixtures/booktoki/attention_required.html");
+
+fn domain(cache: &std::path::Path) -> Arc<DomainResolver> {
+    Arc::new(DomainResolver::new(
+        "booktoki",
+        Url::parse(BASE).unwrap(),
+        Default::default(),
+        Booktoki::SITE_MARKERS,
+        &cache.to_string_lossy(),
+    ))
+}
+
+/// 录制若干响应后返回回放模式的站点实例
+fn replay_site(name: &str, interactions: Vec<Interaction>) -> Booktoki {
+    let dir = temp_dir(name);
+    let recorder = Cassette::new(&dir, CassetteMode::Record);
+    for interaction in interactions {
+        recorder.record(interaction).unwrap();
+    }
+    Booktoki::new(SiteConfig::default(), replay_context(&dir))
+}
+
+#[test]
+fn parses_chapter_list_and_next_page() {
+    let dir = temp_dir("chapters");
+    let indexer = BooktokiIndexer::new(domain(&dir));
+    let base = Url::parse(&format!("{}/novel/123", BASE)).unwrap();
+
+    let (items, next) = indexer.parse_chapters_html(CHAPTERS, &base).unwrap();
+
+    let chapters: Vec<_> = items
+        .iter()
+        .map(|item| match item {
+            BookItem::Chapter(c) => (c.index, c.id.as_str(), c.title.as_str(), c.url.as_str()),
+            _ => panic!("unexpected volume"),
+        })
+        .collect();
+    assert_eq!(
+        chapters,
+        vec![
+            (
+                3,
+                "3",
+                "제3화 새로운 시작",
+                "https://booktoki469.com/novel/1003?spage=1"
+            ),
+            (
+                2,
+                "2",
+                "제2화 만남",
+                "https://booktoki469.com/novel/1002?spage=1"
+            ),
+        ]
+    );
+    assert_eq!(
+        next.as_deref(),
+        Some("https://booktoki469.com/novel/123?spage=2")
+    );
+}
+
+#[test]
+fn empty_chapter_list_has_no_next_page() {
+    let dir = temp_dir("chapters-empty");
+    let indexer = BooktokiIndexer::new(domain(&dir));
+    let base = Url::parse(BASE).unwrap();
+
+    let (items, next) = indexer.parse_chapters_html(CHAPTERS_EMPTY, &base).unwrap();
+    assert!(items.is_empty());
+    assert!(next.is_none());
+}
+
+#[test]
+fn parses_search_results() {
+    let dir = temp_dir("search");
+    let searcher = BooktokiSearcher::new(domain(&dir));
+    let base = Url::parse(&format!("{}/novel?stx=test&page=1", BASE)).unwrap();
+
+    let results = searcher.parse_search_html(SEARCH, &base);
+
+    let summary: Vec<_> = results
+        .iter()
+        .map(|r| (r.id.as_str(), r.title.as_str(), r.cover_url.as_deref()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                "1001",
+                "마법사의 귀환",
+                Some("https://booktoki469.com/data/cover/1001.jpg")
+            ),
+            (
+                "1002",
+                "Unknown Title",
+                Some("https://cdn.booktoki469.com/cover/1002.png")
+            ),
+        ]
+    );
+    assert_eq!(results[0].author.as_deref(), Some("홍길동"));
+    assert_eq!(results[0].tags, ["판타지", "현대"]);
+    assert_eq!(results[0].chapter_count, Some(120));
+    assert!(results[1].author.is_none() && results[1].tags.is_empty());
+}
+
+#[tokio::test]
+async fn empty_search_page_yields_no_results() {
+    let url = format!("{}/novel?stx=zzz&page=1", BASE);
+    let site = replay_site(
+        "search-empty",
+        vec![
+            Interaction::new("GET", &url, 200, SEARCH_EMPTY.as_bytes())
+                .with_header("content-type", "text/html; charset=utf-8"),
+        ],
+    );
+
+    let results = site.searcher.search("zzz", 1, &site.client).await.unwrap();
+    assert!(results.is_empty());
+}
+
+#[tokio::test]
+async fn fetches_content_from_replay() {
+    let url = format!("{}/novel/1001", BASE);
+    let site = replay_site(
+        "content",
+        vec![
+            Interaction::new("GET", &url, 200, CONTENT.as_bytes())
+                .with_header("content-type", "text/html; charset=utf-8"),
+        ],
+    );
+
+    let (content, next) = site
+        .fetcher
+        .fetch_content(&url, &site.client)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        content,
+        "<p>첫 번째 문단입니다.</p>\n<p>두 번째 문단, 쉼표가 있습니다.</p>"
+    );
+    assert_eq!(
+        next.as_deref(),
+        Some("https://booktoki469.com/novel/1002?spage=1")
+    );
+}
+
+#[tokio::test]
+async fn fetches_chapters_from_replay() {
+    let url = format!("{}/novel/123", BASE);
+    let site = replay_site(
+        "chapters-replay",
+        vec![Interaction::new("GET", &url, 200, CHAPTERS.as_bytes())],
+    );
+
+    let (items, next) = site
+        .indexer
+        .fetch_chapters_by_url(&url, &site.client)
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 2);
+    assert!(next.is_some());
+}
+
+#[tokio::test]
+async fn replay_miss_is_an_error() {
+    let site = replay_site("miss", Vec::new());
+    let result = site
+        .fetcher
+        .fetch_content(&format!("{}/novel/404", BASE), &site.client)
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn mirror_discovery_skips_parked_domains() {
+    let site = replay_site(
+        "mirror",
+        vec![
+            Interaction::new("GET", "https://booktoki469.com/", 404, b"gone"),
+            Interaction::new(
+                "GET",
+                "https://booktoki470.com/",
+                200,
+                b"<title>booktoki470.com is for sale</title>",
+            ),
+            Interaction::new("GET", "https://booktoki471.com/", 200, NOVEL.as_bytes()),
+        ],
+    );
+
+    let base = site.domain.resolve(&site.client.ctx).await.unwrap();
+    assert_eq!(base.as_str(), "https://booktoki471.com/");
+}
+
+#[tokio::test]
+async fn parked_current_domain_triggers_discovery() {
+    let site = replay_site(
+        "mirror-parked",
+        vec![
+            Interaction::new(
+                "GET",
+                "https://booktoki469.com/",
+                200,
+                b"<title>booktoki469.com is for sale</title>",
+            ),
+            Interaction::new("GET", "https://booktoki470.com/", 200, NOVEL.as_bytes()),
+        ],
+    );
+
+    let base = site.domain.resolve(&site.client.ctx).await.unwrap();
+    assert_eq!(base.as_str(), "https://booktoki470.com/");
+}
+
+#[tokio::test]
+async fn redirects_adopt_only_verified_mirrors() {
+    let site = replay_site(
+        "mirror-adopt",
+        vec![
+            Interaction::new(
+                "GET",
+                "https://booktoki470.com/",
+                200,
+                b"<title>booktoki470.com is for sale</title>",
+            ),
+            Interaction::new("GET", "https://booktoki471.com/", 200, NOVEL.as_bytes()),
+        ],
+    );
+    let (domain, ctx) = (&site.domain, &site.client.ctx);
+
+    // 其他顶级域名下的同名编号域名不属于镜像族
+    assert!(!domain.is_mirror_host("booktoki470.example.net"));
+    assert!(!domain.adopt(ctx, "https://booktoki470.example.net/").await);
+
+    assert!(!domain.adopt(ctx, "https://booktoki470.com/novel/1").await);
+    assert_eq!(domain.current().as_str(), "https://booktoki469.com/");
+
+    assert!(domain.adopt(ctx, "https://booktoki471.com/novel/1").await);
+    assert_eq!(domain.current().as_str(), "https://booktoki471.com/");
+}
+
+#[tokio::test]
+async fn missing_content_saves_snapshot() {
+    let url = format!("{}/novel/1001", BASE);
+    let site = replay_site(
+        "snapshot",
+        vec![Interaction::new(
+            "GET",
+            &url,
+            200,
+            CHAPTERS_EMPTY.as_bytes(),
+        )],
+    );
+
+    let err = site
+        .fetcher
+        .fetch_content(&url, &site.client)
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("div#novel_content"), "{}", err);
+
+    let dir = err
+        .split("snapshot: ")
+        .nth(1)
+        .map(|rest| rest.trim_end_matches(')'))
+        .expect("snapshot path in error");
+    let dir = std::path::Path::new(dir);
+    assert_eq!(
+        std::fs::read_to_string(dir.join("body.html")).unwrap(),
+        CHAPTERS_EMPTY
+    );
+    let meta = std::fs::read_to_string(dir.join("meta.json")).unwrap();
+    assert!(meta.contains("\"status\": 200"), "{}", meta);
+}
+
+#[tokio::test]
+async fn diagnosis_passes_on_known_markup() {
+    let novel = format!("{}/novel/123", BASE);
+    let chapter = format!("{}/novel/1001?spage=1", BASE);
+    let site = replay_site(
+        "doctor",
+        vec![
+            Interaction::new("GET", &novel, 200, NOVEL.as_bytes()),
+            Interaction::new("GET", &chapter, 200, CONTENT.as_bytes()),
+        ],
+    );
+
+    let mut args = TaskArgs::new();
+    args.insert("id".into(), "123".into());
+    let checks = site.run_diagnosis(&args).await;
+
+    let failed: Vec<_> = checks.iter().filter(|c| !c.passed).collect();
+    assert!(failed.is_empty(), "{:#?}", failed);
+    for stage in ["metadata", "chapters", "content"] {
+        assert!(checks.iter().any(|c| c.stage == stage), "missing {}", stage);
+    }
+}
+
+#[tokio::test]
+async fn diagnosis_reports_selector_drift() {
+    let novel = format!("{}/novel/123", BASE);
+    let site = replay_site(
+        "doctor-drift",
+        vec![Interaction::new("GET", &novel, 200, CONTENT.as_bytes())],
+    );
+
+    let mut args = TaskArgs::new();
+    args.insert("id".into(), "123".into());
+    let checks = site.run_diagnosis(&args).await;
+
+    let failed: Vec<_> = checks
+        .iter()
+        .filter(|c| !c.passed)
+        .map(|c| (c.stage.as_str(), c.name.as_str()))
+        .collect();
+    assert!(failed.contains(&("metadata", "detail_desc")));
+    assert!(failed.contains(&("chapters", "list_item")));
+    assert!(failed.contains(&("content", "fetch")));
+}
+
+#[tokio::test]
+async fn challenge_policy_reports_ip_block() {
+    let dir = temp_dir("challenge");
+    let ctx = replay_context(&dir);
+    let resp = Interaction::new("GET", BASE, 403, ATTENTION_REQUIRED.as_bytes())
+        .to_response()
+        .unwrap();
+
+    let policy = ChallengePolicy::new(&Default::default());
+    match policy.check(resp, &ctx).await.unwrap() {
+        PolicyResult::Retry { reason, .. } => assert_eq!(reason, BlockReason::IpBlocked),
+        _ => panic!("expected retry"),
+    }
+}
+
+#[tokio::test]
+async fn challenge_policy_passes_normal_pages() {
+    let dir = temp_dir("challenge-pass");
+    let ctx = replay_context(&dir);
+    let resp = Interaction::new("GET", BASE, 200, CONTENT.as_bytes())
+        .to_response()
+        .unwrap();
+
+    let policy = ChallengePolicy::new(&Default::default());
+    match policy.check(resp, &ctx).await.unwrap() {
+        PolicyResult::Pass(resp) => {
+            assert_eq!(resp.text().await.unwrap(), CONTENT);
+        }
+        _ => panic!("expected pass"),
+    }
+}
+
+#[tokio::test]
+async fn captcha_policy_ignores_plain_redirects() {
+    let dir = temp_dir("captcha");
+    let ctx = replay_context(&dir);
+    let resp = Interaction::new("GET", BASE, 302, b"")
+        .with_header("location", "/novel/123")
+        .to_response()
+        .unwrap();
+
+    let policy = CaptchaPolicy::new(domain(&dir), &Default::default(), &ctx.config.cache_path);
+    assert!(matches!(
+        policy.check(resp, &ctx).await.unwrap(),
+        PolicyResult::Pass(_)
+    ));
+}
+
+#[test]
+fn detects_expired_login() {
+    let dir = temp_dir("auth");
+    let auth = BooktokiAuth::new(domain(&dir));
+    let empty = HeaderMap::new();
+
+    let mut login = HeaderMap::new();
+    login.insert(
+        LOCATION,
+        HeaderValue::from_static("/bbs/login.php?url=%2Fnovel%2F1"),
+    );
+    assert!(auth.is_expired(StatusCode::FOUND, &login, ""));
+
+    let mut other = HeaderMap::new();
+    other.insert(LOCATION, HeaderValue::from_static("/novel/1"));
+    assert!(!auth.is_expired(StatusCode::FOUND, &other, ""));
+
+    assert!(auth.is_expired(StatusCode::OK, &empty, "회원만 로그인 후 이용 가능합니다"));
+    assert!(!auth.is_expired(StatusCode::OK, &empty, CONTENT));
+}
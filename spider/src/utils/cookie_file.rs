@@ -0,0 +1,241 @@
+//! Cookie 文件格式转换 (Cookie File Formats)
+//!
+//! 支持 Netscape `cookies.txt` 与 EditThisCookie 风格的 JSON 数组，用于与桌面浏览器交换会话凭据。
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::error::{Result, SpiderError};
+use crate::network::cookie::CookieSpec;
+
+/// Netscape 格式中标记 HttpOnly 的行前缀
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+/// EditThisCookie / Cookie-Editor 导出的单条记录
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BrowserCookie {
+    domain: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expiration_date: Option<f64>,
+    #[serde(default)]
+    host_only: bool,
+    #[serde(default)]
+    http_only: bool,
+    name: String,
+    #[serde(default = "default_path")]
+    path: String,
+    #[serde(default)]
+    secure: bool,
+    #[serde(default)]
+    session: bool,
+    #[serde(default)]
+    value: String,
+}
+
+fn default_path() -> String {
+    "/".to_string()
+}
+
+/// 自动识别格式并解析 (JSON 数组或 Netscape 文本)
+pub fn parse_cookies(text: &str) -> Result<Vec<CookieSpec>> {
+    if text.trim_start().starts_with('[') {
+        parse_json(text)
+    } else {
+        parse_netscape(text)
+    }
+}
+
+/// 解析 Netscape `cookies.txt`
+///
+/// 每行七个制表符分隔字段：domain, include_subdomains, path, secure, expiry, name, value。
+pub fn parse_netscape(text: &str) -> Result<Vec<CookieSpec>> {
+    let mut cookies = Vec::new();
+
+    for (lineno, raw) in text.lines().enumerate() {
+        let (line, http_only) = match raw.strip_prefix(HTTP_ONLY_PREFIX) {
+            Some(rest) => (rest, true),
+            None => (raw, false),
+        };
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() < 7 {
+            return Err(SpiderError::Parse(format!(
+                "cookies.txt line {}: expected 7 tab-separated fields, found {}",
+                lineno + 1,
+                fields.len()
+            )));
+        }
+
+        let include_subdomains = fields[1].eq_ignore_ascii_case("TRUE");
+        let host = fields[0].trim_start_matches('.');
+        let expires = fields[4].trim().parse::<i64>().ok().filter(|ts| *ts > 0);
+
+        cookies.push(CookieSpec {
+            name: fields[5].to_string(),
+            value: fields[6].trim_end_matches('\r').to_string(),
+            domain: if include_subdomains {
+                format!(".{}", host)
+            } else {
+                host.to_string()
+            },
+            path: fields[2].to_string(),
+            expires,
+            secure: fields[3].eq_ignore_ascii_case("TRUE"),
+            http_only,
+        });
+    }
+
+    Ok(cookies)
+}
+
+/// 解析 EditThisCookie 风格 JSON 数组
+pub fn parse_json(text: &str) -> Result<Vec<CookieSpec>> {
+    let entries: Vec<BrowserCookie> = serde_json::from_str(text)?;
+
+    Ok(entries
+        .into_iter()
+        .map(|c| {
+            let host = c.domain.trim_start_matches('.');
+            CookieSpec {
+                domain: if c.host_only {
+                    host.to_string()
+                } else {
+                    format!(".{}", host)
+                },
+                expires: c
+                    .expiration_date
+                    .filter(|_| !c.session)
+                    .map(|ts| ts as i64),
+                name: c.name,
+                value: c.value,
+                path: c.path,
+                secure: c.secure,
+                http_only: c.http_only,
+            }
+        })
+        .collect())
+}
+
+/// 输出 Netscape `cookies.txt`
+pub fn to_netscape(cookies: &[CookieSpec]) -> String {
+    let mut out = String::from("# Netscape HTTP Cookie File\n");
+
+    for c in cookies {
+        let flag = |b: bool| if b { "TRUE" } else { "FALSE" };
+        out.push_str(&format!(
+            "{}{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+            if c.http_only { HTTP_ONLY_PREFIX } else { "" },
+            c.domain,
+            flag(c.domain.starts_with('.')),
+            c.path,
+            flag(c.secure),
+            c.expires.unwrap_or(0),
+            c.name,
+            c.value
+        ));
+    }
+
+    out
+}
+
+/// 输出 EditThisCookie 风格 JSON 数组
+pub fn to_json(cookies: &[CookieSpec]) -> Result<String> {
+    let entries: Vec<BrowserCookie> = cookies
+        .iter()
+        .map(|c| BrowserCookie {
+            domain: c.domain.clone(),
+            expiration_date: c.expires.map(|ts| ts as f64),
+            host_only: !c.domain.starts_with('.'),
+            http_only: c.http_only,
+            name: c.name.clone(),
+            path: c.path.clone(),
+            secure: c.secure,
+            session: c.expires.is_none(),
+            value: c.value.clone(),
+        })
+        .collect();
+
+    Ok(serde_json::to_string_pretty(&entries)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(name: &str, domain: &str, expires: Option<i64>, http_only: bool) -> CookieSpec {
+        CookieSpec {
+            name: name.to_string(),
+            value: format!("{}-value", name),
+            domain: domain.to_string(),
+            path: "/".to_string(),
+            expires,
+            secure: true,
+            http_only,
+        }
+    }
+
+    #[test]
+    fn netscape_round_trip() {
+        let text = "# Netscape HTTP Cookie File\n\
+                    #HttpOnly_.example.com\tTRUE\t/\tTRUE\t1900000000\tsid\tabc\n\
+                    example.com\tFALSE\t/novel\tFALSE\t0\ttheme\tdark\r\n\
+                    \n\
+                    # comment\n";
+        let cookies = parse_netscape(text).unwrap();
+        assert_eq!(
+            cookies,
+            vec![
+                CookieSpec {
+                    name: "sid".into(),
+                    value: "abc".into(),
+                    domain: ".example.com".into(),
+                    path: "/".into(),
+                    expires: Some(1_900_000_000),
+                    secure: true,
+                    http_only: true,
+                },
+                CookieSpec {
+                    name: "theme".into(),
+                    value: "dark".into(),
+                    domain: "example.com".into(),
+                    path: "/novel".into(),
+                    expires: None,
+                    secure: false,
+                    http_only: false,
+                },
+            ]
+        );
+
+        let exported = to_netscape(&cookies);
+        assert!(exported.contains("#HttpOnly_.example.com\tTRUE\t/\tTRUE\t1900000000\tsid\tabc\n"));
+        assert_eq!(parse_cookies(&exported).unwrap(), cookies);
+    }
+
+    #[test]
+    fn netscape_rejects_short_lines() {
+        let err = parse_netscape("example.com\tFALSE\t/\n").unwrap_err();
+        assert!(err.to_string().contains("line 1"), "{}", err);
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let cookies = vec![
+            spec("sid", ".example.com", Some(1_900_000_000), true),
+            spec("session", "example.com", None, false),
+        ];
+
+        let exported = to_json(&cookies).unwrap();
+        assert!(exported.contains("\"session\": true"), "{}", exported);
+        assert_eq!(parse_cookies(&exported).unwrap(), cookies);
+
+        // 会话 Cookie 即使带有过期时间也按会话处理
+        let browser = r#"[{"domain": "example.com", "hostOnly": true, "name": "a",
+                           "value": "1", "session": true, "expirationDate": 1900000000.5}]"#;
+        let parsed = parse_json(browser).unwrap();
+        assert_eq!(parsed[0].expires, None);
+        assert_eq!(parsed[0].path, "/");
+    }
+}
@@ -7,6 +7,9 @@ use std::path::Path;
 use tokio::fs;
 use url::Url;
 
+pub mod cookie_file;
+pub mod node_filter;
+pub mod region;
 pub mod subscription;
 
 /// 执行 URI 规范化 (URI Normalization)
@@ -53,6 +56,14 @@ pub fn generate_filename(url: &str) -> String {
     format!("{}.{}", hash, ext)
 }
 
+/// 当前 Unix 时间戳 (秒)
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
 /// 检查物理文件是否存在 (Non-blocking)
 pub async fn file_exists(path: impl AsRef<Path>) -> bool {
     tokio::fs::try_exists(path).await.unwrap_or(false)
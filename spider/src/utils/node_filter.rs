@@ -0,0 +1,234 @@
+//! 节点筛选 (Node Filtering)
+//!
+//! 按 `[proxy.filter]` 的标签正则、协议白名单与服务器排除规则过滤订阅节点，
+//! 并按地区偏好对节点排序。
+
+use std::net::IpAddr;
+
+use anyhow::{Context as _, Result};
+use regex::Regex;
+
+use crate::core::config::NodeFilterConfig;
+use crate::utils::region;
+use crate::utils::subscription::ProxyNode;
+
+/// 服务器排除规则
+#[derive(Debug)]
+enum ServerRule {
+    /// 域名及其子域名
+    Domain(String),
+    /// IP 段
+    Cidr(IpAddr, u8),
+}
+
+impl ServerRule {
+    fn parse(rule: &str) -> Result<Self> {
+        let rule = rule.trim().trim_start_matches('.').to_ascii_lowercase();
+        let (addr, prefix) = match rule.split_once('/') {
+            Some((addr, bits)) => (
+                addr,
+                Some(
+                    bits.parse::<u8>()
+                        .with_context(|| format!("Invalid CIDR prefix: {}", rule))?,
+                ),
+            ),
+            None => (rule.as_str(), None),
+        };
+
+        match addr.parse::<IpAddr>() {
+            Ok(ip) => {
+                let max = if ip.is_ipv4() { 32 } else { 128 };
+                let prefix = prefix.unwrap_or(max);
+                anyhow::ensure!(prefix <= max, "Invalid CIDR prefix: {}", rule);
+                Ok(Self::Cidr(ip, prefix))
+            }
+            Err(_) if prefix.is_none() => Ok(Self::Domain(rule)),
+            Err(_) => anyhow::bail!("Invalid CIDR address: {}", rule),
+        }
+    }
+
+    fn matches(&self, server: &str) -> bool {
+        let server = server.trim_start_matches('[').trim_end_matches(']');
+        match self {
+            Self::Domain(domain) => {
+                let server = server.to_ascii_lowercase();
+                server == *domain || server.ends_with(&format!(".{}", domain))
+            }
+            Self::Cidr(net, prefix) => match (server.parse::<IpAddr>(), net) {
+                (Ok(IpAddr::V4(ip)), IpAddr::V4(net)) => {
+                    mask_eq(u32::from(ip) as u128, u32::from(*net) as u128, *prefix, 32)
+                }
+                (Ok(IpAddr::V6(ip)), IpAddr::V6(net)) => {
+                    mask_eq(u128::from(ip), u128::from(*net), *prefix, 128)
+                }
+                _ => false,
+            },
+        }
+    }
+}
+
+/// 比较两个地址的前 `prefix` 位
+fn mask_eq(a: u128, b: u128, prefix: u8, width: u8) -> bool {
+    if prefix == 0 {
+        return true;
+    }
+    let shift = (width - prefix) as u32;
+    (a >> shift) == (b >> shift)
+}
+
+/// 已编译的节点筛选规则
+#[derive(Debug, Default)]
+pub struct NodeFilter {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+    protocols: Vec<String>,
+    servers: Vec<ServerRule>,
+}
+
+impl NodeFilter {
+    pub fn new(config: &NodeFilterConfig) -> Result<Self> {
+        let compile = |patterns: &[String], key: &str| {
+            patterns
+                .iter()
+                .map(|p| {
+                    Regex::new(p).with_context(|| format!("Invalid proxy.filter.{}: {}", key, p))
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+
+        Ok(Self {
+            include: compile(&config.include_tags, "include_tags")?,
+            exclude: compile(&config.exclude_tags, "exclude_tags")?,
+            protocols: config
+                .protocols
+                .iter()
+                .map(|p| normalize_protocol(p).to_string())
+                .collect(),
+            servers: config
+                .exclude_servers
+                .iter()
+                .map(|rule| ServerRule::parse(rule))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// 检查节点是否保留，排除时返回原因
+    pub fn check(&self, node: &ProxyNode) -> std::result::Result<(), String> {
+        let tag = node.tag();
+        if !self.include.is_empty() && !self.include.iter().any(|re| re.is_match(tag)) {
+            return Err("tag not included".into());
+        }
+        if let Some(re) = self.exclude.iter().find(|re| re.is_match(tag)) {
+            return Err(format!("tag excluded by `{}`", re.as_str()));
+        }
+        if !self.protocols.is_empty() && !self.protocols.iter().any(|p| p == node.protocol()) {
+            return Err(format!("protocol {} not allowed", node.protocol()));
+        }
+        if self.servers.iter().any(|rule| rule.matches(node.server())) {
+            return Err("server excluded".into());
+        }
+        Ok(())
+    }
+}
+
+/// 协议别名归一 (`ss` -> `shadowsocks`，`socks5` -> `socks`)
+fn normalize_protocol(name: &str) -> &str {
+    match name.trim() {
+        "ss" => "shadowsocks",
+        "socks5" => "socks",
+        "https" => "http",
+        other => other,
+    }
+}
+
+/// 地区偏好
+#[derive(Debug, Clone, Default)]
+pub struct RegionPreference {
+    /// 偏好顺序 (已归一化的地区代码)
+    pub regions: Vec<String>,
+    /// 仅使用偏好地区的节点
+    pub only: bool,
+}
+
+impl RegionPreference {
+    pub fn new(regions: &[String], only: bool) -> Self {
+        Self {
+            regions: regions.iter().map(|r| region::normalize(r)).collect(),
+            only,
+        }
+    }
+
+    /// 地区在偏好中的次序 (越小越优先，未列出者为 `None`)
+    pub fn rank(&self, region: Option<&str>) -> Option<usize> {
+        let region = region?;
+        self.regions.iter().position(|r| r == region)
+    }
+
+    /// 按地区偏好对已排序的节点下标做稳定重排
+    ///
+    /// `only` 时剔除未列出地区的节点；剔除后为空则保留原顺序。
+    pub fn apply(
+        &self,
+        ranked: Vec<usize>,
+        region_of: impl Fn(usize) -> Option<&'static str>,
+    ) -> Vec<usize> {
+        if self.regions.is_empty() {
+            return ranked;
+        }
+
+        let mut keyed: Vec<(usize, usize)> = ranked
+            .iter()
+            .map(|&i| (self.rank(region_of(i)).unwrap_or(usize::MAX), i))
+            .collect();
+        keyed.sort_by_key(|(rank, _)| *rank);
+
+        if self.only {
+            let preferred: Vec<usize> = keyed
+                .iter()
+                .filter(|(rank, _)| *rank != usize::MAX)
+                .map(|(_, i)| *i)
+                .collect();
+            if !preferred.is_empty() {
+                return preferred;
+            }
+        }
+        keyed.into_iter().map(|(_, i)| i).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_rules_match_domains_and_cidrs() {
+        let domain = ServerRule::parse("example.com").unwrap();
+        assert!(domain.matches("example.com"));
+        assert!(domain.matches("a.Example.com"));
+        assert!(!domain.matches("badexample.com"));
+
+        let v4 = ServerRule::parse("10.0.0.0/8").unwrap();
+        assert!(v4.matches("10.1.2.3"));
+        assert!(!v4.matches("11.0.0.1"));
+        assert!(!v4.matches("example.com"));
+
+        let v6 = ServerRule::parse("2001:db8::/32").unwrap();
+        assert!(v6.matches("[2001:db8::1]"));
+        assert!(ServerRule::parse("10.0.0.0/33").is_err());
+    }
+
+    #[test]
+    fn region_preference_orders_and_restricts() {
+        let regions = [Some("US"), Some("JP"), None, Some("KR")];
+        let region_of = |i: usize| regions[i];
+
+        let prefer = RegionPreference::new(&["kr".into(), "jp".into()], false);
+        assert_eq!(prefer.apply(vec![0, 1, 2, 3], region_of), vec![3, 1, 0, 2]);
+
+        let only = RegionPreference::new(&["KR".into()], true);
+        assert_eq!(only.apply(vec![0, 1, 2, 3], region_of), vec![3]);
+
+        let missing = RegionPreference::new(&["DE".into()], true);
+        assert_eq!(missing.apply(vec![0, 1], region_of), vec![0, 1]);
+    }
+}
@@ -0,0 +1,134 @@
+//! 节点地区识别 (Node Region Detection)
+//!
+//! 从订阅节点标签中的旗帜 Emoji、国家/城市名称或地区代码推断出口地区，
+//! 统一归一为 ISO 3166-1 alpha-2 代码 (如 `KR`、`JP`)。
+
+/// 地区代码及其常见名称 (中文、英文及主要城市)
+const REGIONS: &[(&str, &[&str])] = &[
+    ("HK", &["香港", "Hong Kong", "HongKong"]),
+    ("TW", &["台湾", "臺灣", "Taiwan", "台北", "Taipei"]),
+    (
+        "JP",
+        &["日本", "Japan", "东京", "東京", "Tokyo", "大阪", "Osaka"],
+    ),
+    (
+        "KR",
+        &[
+            "韩国", "韓國", "한국", "Korea", "首尔", "Seoul", "서울", "春川", "釜山", "Busan",
+        ],
+    ),
+    ("SG", &["新加坡", "狮城", "Singapore"]),
+    (
+        "US",
+        &[
+            "美国",
+            "United States",
+            "USA",
+            "America",
+            "洛杉矶",
+            "Los Angeles",
+            "硅谷",
+            "圣何塞",
+            "San Jose",
+            "纽约",
+            "New York",
+        ],
+    ),
+    (
+        "GB",
+        &["英国", "United Kingdom", "Britain", "伦敦", "London"],
+    ),
+    ("DE", &["德国", "Germany", "法兰克福", "Frankfurt"]),
+    ("FR", &["法国", "France", "巴黎", "Paris"]),
+    ("NL", &["荷兰", "Netherlands", "阿姆斯特丹", "Amsterdam"]),
+    ("CA", &["加拿大", "Canada"]),
+    ("AU", &["澳大利亚", "澳洲", "Australia", "悉尼", "Sydney"]),
+    ("RU", &["俄罗斯", "Russia", "莫斯科", "Moscow"]),
+    ("IN", &["印度", "India", "孟买", "Mumbai"]),
+    ("TR", &["土耳其", "Turkey", "Türkiye"]),
+    ("MY", &["马来西亚", "Malaysia"]),
+    ("TH", &["泰国", "Thailand"]),
+    ("VN", &["越南", "Vietnam"]),
+    ("PH", &["菲律宾", "Philippines"]),
+    ("ID", &["印尼", "印度尼西亚", "Indonesia"]),
+    ("BR", &["巴西", "Brazil"]),
+    ("AR", &["阿根廷", "Argentina"]),
+    ("CN", &["中国", "China", "回国"]),
+];
+
+/// 归一化用户填写的地区代码 (`uk` -> `GB`)
+pub fn normalize(code: &str) -> String {
+    match code.trim().to_ascii_uppercase().as_str() {
+        "UK" => "GB".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// 从节点标签推断地区
+///
+/// 依次尝试旗帜 Emoji、名称与独立的大写地区代码 (如 `KR 01`、`JP-Tokyo`、`US02`)。
+pub fn detect(tag: &str) -> Option<&'static str> {
+    from_flag(tag)
+        .or_else(|| from_name(tag))
+        .or_else(|| from_code(tag))
+}
+
+/// 旗帜 Emoji 由两个区域指示符组成 (U+1F1E6..U+1F1FF 对应 A..Z)
+fn from_flag(tag: &str) -> Option<&'static str> {
+    let indicator = |c: char| {
+        let c = c as u32;
+        (0x1F1E6..=0x1F1FF)
+            .contains(&c)
+            .then(|| (b'A' + (c - 0x1F1E6) as u8) as char)
+    };
+
+    let chars: Vec<char> = tag.chars().collect();
+    chars.windows(2).find_map(|pair| {
+        let code: String = [indicator(pair[0])?, indicator(pair[1])?].iter().collect();
+        lookup(&normalize(&code))
+    })
+}
+
+fn from_name(tag: &str) -> Option<&'static str> {
+    let lower = tag.to_lowercase();
+    REGIONS
+        .iter()
+        .find(|(_, names)| {
+            names
+                .iter()
+                .any(|name| lower.contains(&name.to_lowercase()))
+        })
+        .map(|(code, _)| *code)
+}
+
+fn from_code(tag: &str) -> Option<&'static str> {
+    tag.split(|c: char| !c.is_ascii_alphanumeric())
+        .map(|token| token.trim_end_matches(|c: char| c.is_ascii_digit()))
+        .filter(|token| token.len() == 2 && token.chars().all(|c| c.is_ascii_uppercase()))
+        .find_map(|token| lookup(&normalize(token)))
+}
+
+fn lookup(code: &str) -> Option<&'static str> {
+    REGIONS
+        .iter()
+        .find(|(known, _)| *known == code)
+        .map(|(code, _)| *code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_regions_from_tags() {
+        assert_eq!(detect("🇰🇷 Korea 01"), Some("KR"));
+        assert_eq!(detect("🇯🇵东京 IEPL"), Some("JP"));
+        assert_eq!(detect("韩国春川 | 1x"), Some("KR"));
+        assert_eq!(detect("US02 高速"), Some("US"));
+        assert_eq!(detect("[UK] London"), Some("GB"));
+        assert_eq!(detect("JP-Osaka-03"), Some("JP"));
+        assert_eq!(detect("剩余流量 10G"), None);
+        // 小写单词不视为地区代码
+        assert_eq!(detect("fast us node"), None);
+    }
+}